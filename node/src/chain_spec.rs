@@ -1,7 +1,7 @@
 use sp_core::{Pair, Public, sr25519};
 use node_template_runtime::{
 	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	SudoConfig, SystemConfig, KittiesModuleConfig, WASM_BINARY, Signature
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
@@ -62,6 +62,13 @@ pub fn development_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 			],
+			// Pre-minted kitties
+			vec![
+				(get_account_id_from_seed::<sr25519::Public>("Alice"), None),
+				(get_account_id_from_seed::<sr25519::Public>("Alice"), None),
+				(get_account_id_from_seed::<sr25519::Public>("Bob"), None),
+				(get_account_id_from_seed::<sr25519::Public>("Bob"), None),
+			],
 			true,
 		),
 		// Bootnodes
@@ -110,6 +117,13 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 			],
+			// Pre-minted kitties
+			vec![
+				(get_account_id_from_seed::<sr25519::Public>("Alice"), None),
+				(get_account_id_from_seed::<sr25519::Public>("Alice"), None),
+				(get_account_id_from_seed::<sr25519::Public>("Bob"), None),
+				(get_account_id_from_seed::<sr25519::Public>("Bob"), None),
+			],
 			true,
 		),
 		// Bootnodes
@@ -131,6 +145,7 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	initial_kitties: Vec<(AccountId, Option<[u8; 16]>)>,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -153,5 +168,9 @@ fn testnet_genesis(
 			// Assign network admin rights.
 			key: root_key,
 		}),
+		pallet_kitties: Some(KittiesModuleConfig {
+			// Owners must be among the endowed accounts to cover the kitty deposit.
+			kitties: initial_kitties,
+		}),
 	}
 }
@@ -0,0 +1,245 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Randomness built from secrets that accounts commit to and reveal later.
+//!
+//! Time is cut into rounds of `RoundLength` blocks. An account commits to a secret by
+//! submitting the hash of the account and the secret, reserving `CommitDeposit`. During the
+//! round after the one it committed in, it reveals the secret and gets its deposit back. At the
+//! end of that round every secret revealed in it is mixed into the random seed at once, and the
+//! commitments left unrevealed are dropped and their deposit is slashed.
+//!
+//! Nobody knows a secret before it is revealed, so unlike `pallet_randomness_collective_flip`
+//! the seed cannot be predicted or ground by block authors. A committer can still withhold its
+//! reveal once it has seen the other reveals of the round, which costs it the deposit, so an
+//! account controlling several commitments of a round can choose between as many outcomes as it
+//! pays deposits for. Block authors may leave reveals out of their blocks; a reveal that every
+//! author of the round leaves out is slashed like a withheld one.
+//!
+//! When nothing was revealed in a round, the hash of the parent block is mixed in instead, so
+//! that the seed keeps changing on chains nobody commits on. Block authors can influence that
+//! hash, so such rounds are only as good as `pallet_randomness_collective_flip`.
+//!
+//! The seed is the default hash until the first round closes, and anybody can read its current
+//! value. Callers must not use the value current when a request is made, but wait for a round of
+//! commitments made after the request to close, as `FreshRandomness` lets them do. The kitties
+//! pallet hatches kitties that way.
+
+use codec::{Encode, Decode};
+use frame_support::{decl_module, decl_storage, decl_event, decl_error, ensure,
+	StorageValue, StorageMap, StorageDoubleMap,
+	traits::{Currency, ReservableCurrency, Randomness, Get},
+	weights::Weight,
+};
+use frame_system::ensure_signed;
+use sp_runtime::{RuntimeDebug, traits::{Hash, One, Saturating, Zero}};
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+
+/// A commitment waiting to be revealed.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct Commitment<Hash, BlockNumber, Balance> {
+	/// `T::Hashing` hash of the committing account and its secret.
+	pub hash: Hash,
+	/// Block in which the commitment was made. The secret may be revealed from the start of
+	/// the next round on.
+	pub committed_at: BlockNumber,
+	/// Last block in which the secret may be revealed, the last one of the next round.
+	pub deadline: BlockNumber,
+	pub deposit: Balance,
+}
+
+type CommitmentOf<T> = Commitment<
+	<T as frame_system::Trait>::Hash,
+	<T as frame_system::Trait>::BlockNumber,
+	BalanceOf<T>,
+>;
+
+/// Randomness that tells which commitments it is made of.
+pub trait FreshRandomness<Output, BlockNumber> {
+	/// A value derived from `subject` and the current seed, and the first block of the round
+	/// whose commitments were last mixed into the seed. The value could not be known by anybody
+	/// before that block.
+	fn fresh_random(subject: &[u8]) -> (Output, BlockNumber);
+}
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Trait: frame_system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+	/// The currency in which commitment deposits are taken.
+	type Currency: ReservableCurrency<Self::AccountId>;
+	/// Deposit reserved with every commitment, slashed when it is not revealed in time.
+	type CommitDeposit: Get<BalanceOf<Self>>;
+	/// Blocks in a round. Secrets committed to in a round are revealed in the next one. Must
+	/// not be zero.
+	type RoundLength: Get<Self::BlockNumber>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as CommitReveal {
+		/// Hash of every secret revealed in a closed round so far.
+		pub Seed get(fn seed): T::Hash;
+		/// First block of the round whose commitments were last mixed into `Seed`.
+		pub SeedSince get(fn seed_since): T::BlockNumber;
+		/// Hash of the secrets revealed in the current round, and how many there are.
+		pub Revealed get(fn revealed): Option<(T::Hash, u32)>;
+		/// Open commitment of an account.
+		pub Commitments get(fn commitment): map hasher(blake2_128_concat) T::AccountId => Option<CommitmentOf<T>>;
+		/// Commitments to drop at the end of a block. (deadline, account)
+		pub CommitmentsExpiring get(fn commitments_expiring): double_map hasher(twox_64_concat) T::BlockNumber, hasher(blake2_128_concat) T::AccountId => ();
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as frame_system::Trait>::AccountId,
+		<T as frame_system::Trait>::Hash,
+		<T as frame_system::Trait>::BlockNumber,
+		Balance = BalanceOf<T>,
+	{
+		/// An account committed to a secret. [who, commitment]
+		Committed(AccountId, Hash),
+		/// A secret was revealed. It is mixed into the seed when the round closes. [who, secret]
+		Revealed(AccountId, Hash),
+		/// A commitment was not revealed in time and its deposit was slashed. [who, deposit]
+		RevealMissed(AccountId, Balance),
+		/// The secrets of the commitments made from a block on were mixed into the seed, or the
+		/// parent block hash if none was revealed. [committed_from, reveals]
+		RoundClosed(BlockNumber, u32),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The account already has an open commitment.
+		AlreadyCommitted,
+		/// The account has no open commitment.
+		NoCommitment,
+		/// Secrets may only be revealed in the round after the one of their commitment.
+		RevealTooEarly,
+		/// The secret does not match the commitment.
+		InvalidReveal,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let expiring = <CommitmentsExpiring<T>>::iter_prefix(now).count() as Weight;
+			let closing = if Self::closes_round(now) { 3 } else { 0 };
+			T::DbWeight::get().reads_writes(1 + 2 * expiring + closing, 1 + 2 * expiring + closing)
+		}
+
+		fn on_finalize(now: T::BlockNumber) {
+			Self::drop_expired(now);
+			if Self::closes_round(now) {
+				Self::close_round(now);
+			}
+		}
+
+		/// Commit to a secret by submitting `commitment_of(who, secret)`.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(2, 3)]
+		pub fn commit(origin, hash: T::Hash) {
+			let who = ensure_signed(origin)?;
+			ensure!(!<Commitments<T>>::contains_key(&who), Error::<T>::AlreadyCommitted);
+
+			let deposit = T::CommitDeposit::get();
+			T::Currency::reserve(&who, deposit)?;
+
+			let committed_at = <frame_system::Module<T>>::block_number();
+			let round_length = T::RoundLength::get();
+			let round_start = committed_at - committed_at % round_length;
+			let deadline = round_start.saturating_add(round_length + round_length) - One::one();
+			<Commitments<T>>::insert(&who, Commitment { hash, committed_at, deadline, deposit });
+			<CommitmentsExpiring<T>>::insert(deadline, &who, ());
+
+			Self::deposit_event(RawEvent::Committed(who, hash));
+		}
+
+		/// Reveal the secret of your commitment in the round after the one you committed in,
+		/// getting the deposit back. It is mixed into the seed when the round closes.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(3, 4)]
+		pub fn reveal(origin, secret: T::Hash) {
+			let who = ensure_signed(origin)?;
+			let commitment = Self::commitment(&who).ok_or(Error::<T>::NoCommitment)?;
+			let round_start = commitment.deadline.saturating_add(One::one()).saturating_sub(T::RoundLength::get());
+			ensure!(
+				<frame_system::Module<T>>::block_number() >= round_start,
+				Error::<T>::RevealTooEarly
+			);
+			ensure!(Self::commitment_of(&who, &secret) == commitment.hash, Error::<T>::InvalidReveal);
+
+			<Commitments<T>>::remove(&who);
+			<CommitmentsExpiring<T>>::remove(commitment.deadline, &who);
+			T::Currency::unreserve(&who, commitment.deposit);
+			<Revealed<T>>::mutate(|revealed| {
+				let (hash, count) = revealed.take().unwrap_or_default();
+				*revealed = Some((T::Hashing::hash_of(&(hash, secret)), count.saturating_add(1)));
+			});
+
+			Self::deposit_event(RawEvent::Revealed(who, secret));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The hash `who` has to commit to in order to reveal `secret` later.
+	pub fn commitment_of(who: &T::AccountId, secret: &T::Hash) -> T::Hash {
+		T::Hashing::hash_of(&(who, secret))
+	}
+
+	/// Whether block `now` is the last of a round in which secrets could be revealed.
+	fn closes_round(now: T::BlockNumber) -> bool {
+		let round_length = T::RoundLength::get();
+		let next = now.saturating_add(One::one());
+		(next % round_length).is_zero() && next >= round_length + round_length
+	}
+
+	/// Mix the secrets revealed in the round ending with block `now` into the seed, or the
+	/// parent block hash if there are none. Their commitments were made in the round before.
+	fn close_round(now: T::BlockNumber) {
+		let round_length = T::RoundLength::get();
+		let since = now.saturating_add(One::one()).saturating_sub(round_length + round_length);
+		let (revealed, count) = <Revealed<T>>::take()
+			.unwrap_or_else(|| (<frame_system::Module<T>>::parent_hash(), 0));
+		<Seed<T>>::put(T::Hashing::hash_of(&(Self::seed(), revealed)));
+		<SeedSince<T>>::put(since);
+		Self::deposit_event(RawEvent::RoundClosed(since, count));
+	}
+
+	/// Drop the commitments whose reveal period ends at block `now`, slashing their deposit.
+	fn drop_expired(now: T::BlockNumber) {
+		let expiring = <CommitmentsExpiring<T>>::iter_prefix(now).map(|(who, _)| who).collect::<Vec<_>>();
+		<CommitmentsExpiring<T>>::remove_prefix(now);
+		for who in expiring {
+			if let Some(commitment) = <Commitments<T>>::take(&who) {
+				let _ = T::Currency::slash_reserved(&who, commitment.deposit);
+				Self::deposit_event(RawEvent::RevealMissed(who, commitment.deposit));
+			}
+		}
+	}
+}
+
+impl<T: Trait> Randomness<T::Hash> for Module<T> {
+	/// A value derived from `subject` and every round closed so far. It only changes when a
+	/// round closes.
+	fn random(subject: &[u8]) -> T::Hash {
+		T::Hashing::hash_of(&(Self::seed(), subject))
+	}
+}
+
+impl<T: Trait> FreshRandomness<T::Hash, T::BlockNumber> for Module<T> {
+	fn fresh_random(subject: &[u8]) -> (T::Hash, T::BlockNumber) {
+		(Self::random(subject), Self::seed_since())
+	}
+}
@@ -0,0 +1,182 @@
+use crate::{Error, mock::*};
+use frame_support::{
+	assert_ok, assert_noop,
+	traits::{OnFinalize, OnInitialize, Randomness},
+};
+use sp_core::H256;
+use sp_runtime::traits::BlakeTwo256;
+use super::*;
+
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		CommitReveal::on_finalize(System::block_number());
+		System::on_finalize(System::block_number());
+		System::set_block_number(System::block_number() + 1);
+		System::on_initialize(System::block_number());
+		CommitReveal::on_initialize(System::block_number());
+	}
+}
+
+/// First block of the next round.
+fn next_round() -> u64 {
+	(System::block_number() / RoundLength::get() + 1) * RoundLength::get()
+}
+
+fn last_event() -> TestEvent {
+	System::events().pop().map(|e| e.event).expect("an event was deposited")
+}
+
+#[test]
+fn commit_and_reveal_works() {
+	new_test_ext().execute_with(|| {
+		let secret = H256::repeat_byte(7);
+		let hash = CommitReveal::commitment_of(&1, &secret);
+		assert_ok!(CommitReveal::commit(Origin::signed(1), hash));
+		assert_eq!(Balances::reserved_balance(1), 100);
+		assert_eq!(CommitReveal::commitment(1).map(|c| c.deadline), Some(9));
+		assert_eq!(last_event(), TestEvent::commit_reveal(RawEvent::Committed(1, hash)));
+
+		let before = CommitReveal::random(b"kitty");
+		run_to_block(5);
+		assert_ok!(CommitReveal::reveal(Origin::signed(1), secret));
+
+		assert_eq!(CommitReveal::commitment(1), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 1_000);
+		assert_eq!(last_event(), TestEvent::commit_reveal(RawEvent::Revealed(1, secret)));
+
+		//轮次结束前种子不变
+		run_to_block(9);
+		assert_eq!(CommitReveal::random(b"kitty"), before);
+
+		run_to_block(10);
+		let revealed = BlakeTwo256::hash_of(&(H256::default(), secret));
+		assert_eq!(CommitReveal::seed(), BlakeTwo256::hash_of(&(H256::default(), revealed)));
+		assert_ne!(CommitReveal::random(b"kitty"), before);
+		assert_eq!(CommitReveal::fresh_random(b"kitty"), (CommitReveal::random(b"kitty"), 0));
+		assert!(System::events().iter().any(|e|
+			e.event == TestEvent::commit_reveal(RawEvent::RoundClosed(0, 1))
+		));
+
+		//过期时不会再惩罚已经揭示的承诺
+		assert_eq!(Balances::free_balance(1), 1_000);
+	})
+}
+
+#[test]
+fn random_depends_on_subject_and_reveals() {
+	new_test_ext().execute_with(|| {
+		assert_ne!(CommitReveal::random(b"a"), CommitReveal::random(b"b"));
+		assert_eq!(CommitReveal::random_seed(), CommitReveal::random(&[]));
+
+		//揭示顺序不同得到不同的种子
+		let reveal_all = |order: &[(u64, H256)]| {
+			for (who, secret) in order {
+				assert_ok!(CommitReveal::commit(Origin::signed(*who), CommitReveal::commitment_of(who, secret)));
+			}
+			run_to_block(next_round());
+			for (who, secret) in order {
+				assert_ok!(CommitReveal::reveal(Origin::signed(*who), *secret));
+			}
+			<Seed<Test>>::kill();
+			run_to_block(next_round());
+			CommitReveal::seed()
+		};
+		let (a, b) = ((1, H256::repeat_byte(1)), (2, H256::repeat_byte(2)));
+		let seed = reveal_all(&[a, b]);
+		assert_eq!(reveal_all(&[a, b]), seed);
+		assert_ne!(reveal_all(&[b, a]), seed);
+	})
+}
+
+#[test]
+fn commit_fails_when_already_committed_or_broke() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CommitReveal::commit(Origin::signed(1), H256::repeat_byte(1)));
+		assert_noop!(
+			CommitReveal::commit(Origin::signed(1), H256::repeat_byte(2)),
+			Error::<Test>::AlreadyCommitted
+		);
+		assert_noop!(
+			CommitReveal::commit(Origin::signed(3), H256::repeat_byte(3)),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+	})
+}
+
+#[test]
+fn reveal_fails_for_bad_secrets() {
+	new_test_ext().execute_with(|| {
+		let secret = H256::repeat_byte(7);
+		assert_noop!(CommitReveal::reveal(Origin::signed(1), secret), Error::<Test>::NoCommitment);
+
+		assert_ok!(CommitReveal::commit(Origin::signed(1), CommitReveal::commitment_of(&1, &secret)));
+		assert_noop!(CommitReveal::reveal(Origin::signed(1), secret), Error::<Test>::RevealTooEarly);
+		//同一轮次内不能揭示
+		run_to_block(4);
+		assert_noop!(CommitReveal::reveal(Origin::signed(1), secret), Error::<Test>::RevealTooEarly);
+
+		run_to_block(5);
+		assert_noop!(
+			CommitReveal::reveal(Origin::signed(1), H256::repeat_byte(8)),
+			Error::<Test>::InvalidReveal
+		);
+		//承诺绑定了账户, 其他账户不能揭示
+		assert_noop!(CommitReveal::reveal(Origin::signed(2), secret), Error::<Test>::NoCommitment);
+
+		//轮次第一个区块的承诺要等到下一轮
+		assert_ok!(CommitReveal::commit(Origin::signed(2), CommitReveal::commitment_of(&2, &secret)));
+		run_to_block(9);
+		assert_noop!(CommitReveal::reveal(Origin::signed(2), secret), Error::<Test>::RevealTooEarly);
+		run_to_block(10);
+		assert_ok!(CommitReveal::reveal(Origin::signed(2), secret));
+	})
+}
+
+#[test]
+fn missed_reveal_is_slashed() {
+	new_test_ext().execute_with(|| {
+		let secret = H256::repeat_byte(7);
+		assert_ok!(CommitReveal::commit(Origin::signed(1), CommitReveal::commitment_of(&1, &secret)));
+
+		//揭示轮次的最后一个区块仍然可以揭示
+		run_to_block(9);
+		assert!(CommitReveal::commitment(1).is_some());
+
+		run_to_block(10);
+		assert_eq!(CommitReveal::commitment(1), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 900);
+		assert!(System::events().iter().any(|e|
+			e.event == TestEvent::commit_reveal(RawEvent::RevealMissed(1, 100))
+		));
+		//未揭示的秘密不计入种子
+		assert!(System::events().iter().any(|e|
+			e.event == TestEvent::commit_reveal(RawEvent::RoundClosed(0, 0))
+		));
+
+		assert_noop!(CommitReveal::reveal(Origin::signed(1), secret), Error::<Test>::NoCommitment);
+		//之后可以重新承诺
+		assert_ok!(CommitReveal::commit(Origin::signed(1), CommitReveal::commitment_of(&1, &secret)));
+	})
+}
+
+#[test]
+fn round_without_reveals_mixes_in_parent_hash() {
+	new_test_ext().execute_with(|| {
+		run_to_block(8);
+		assert_eq!(CommitReveal::seed(), H256::default());
+
+		System::initialize(&9, &H256::repeat_byte(9), &Default::default(), &Default::default(), Default::default());
+		run_to_block(10);
+		assert_eq!(CommitReveal::seed(), BlakeTwo256::hash_of(&(H256::default(), H256::repeat_byte(9))));
+		assert_eq!(CommitReveal::seed_since(), 0);
+		assert_eq!(last_event(), TestEvent::commit_reveal(RawEvent::RoundClosed(0, 0)));
+
+		//每一轮结束时种子都会变化
+		let seed = CommitReveal::seed();
+		run_to_block(15);
+		assert_ne!(CommitReveal::seed(), seed);
+		assert_eq!(CommitReveal::seed_since(), 5);
+	})
+}
@@ -0,0 +1,31 @@
+//! Runtime API definition for the kitties pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	/// Read-only queries over kitty ownership and lineage.
+	pub trait KittiesApi<AccountId, KittyIndex> where
+		AccountId: Codec,
+		KittyIndex: Codec,
+	{
+		/// Up to `limit` kitties owned by `account`, skipping the first `start` of them.
+		fn kitties_of(account: AccountId, start: u32, limit: u32) -> Vec<KittyIndex>;
+		/// Number of kitties owned by `account`.
+		fn owned_kitties_count(account: AccountId) -> u32;
+		/// Owner of a kitty, if it exists.
+		fn kitty_owner(kitty_id: KittyIndex) -> Option<AccountId>;
+		/// DNA of a kitty, if it exists and has hatched.
+		fn kitty_dna(kitty_id: KittyIndex) -> Option<[u8; 16]>;
+		/// The two kitties a kitty was bred from. Empty for created kitties.
+		fn parents(kitty_id: KittyIndex) -> Vec<KittyIndex>;
+		/// Kitties bred from a kitty.
+		fn children(kitty_id: KittyIndex) -> Vec<KittyIndex>;
+		/// Kitties a kitty has been bred with.
+		fn breeding_partners(kitty_id: KittyIndex) -> Vec<KittyIndex>;
+		/// Number of kitties ever created.
+		fn kitties_count() -> KittyIndex;
+	}
+}
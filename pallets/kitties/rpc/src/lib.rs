@@ -0,0 +1,231 @@
+//! RPC interface for the kitties pallet.
+//!
+//! Exposes the `KittiesApi` runtime API under the `kitties_*` JSON-RPC namespace.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_kitties_rpc_runtime_api::KittiesApi as KittiesRuntimeApi;
+
+/// A kitty as returned over RPC.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KittyInfo<AccountId, KittyIndex> {
+	/// Index of the kitty.
+	pub id: KittyIndex,
+	/// Current owner.
+	pub owner: AccountId,
+	/// DNA, serialized as a `0x`-prefixed hex string. `null` until the kitty hatches.
+	pub dna: Option<Bytes>,
+}
+
+/// Parents, children and breeding partners of a kitty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KittyLineage<KittyIndex> {
+	/// The two kitties it was bred from. Empty for created kitties.
+	pub parents: Vec<KittyIndex>,
+	/// Kitties bred from it.
+	pub children: Vec<KittyIndex>,
+	/// Kitties it has been bred with.
+	pub breeding_partners: Vec<KittyIndex>,
+}
+
+#[rpc]
+pub trait KittiesApi<BlockHash, AccountId, KittyIndex> {
+	/// Up to `limit` kitties owned by `account`, skipping the first `start` of them.
+	#[rpc(name = "kitties_kittiesOf")]
+	fn kitties_of(
+		&self,
+		account: AccountId,
+		start: u32,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<KittyIndex>>;
+
+	/// Number of kitties owned by `account`.
+	#[rpc(name = "kitties_ownedCount")]
+	fn owned_count(&self, account: AccountId, at: Option<BlockHash>) -> Result<u32>;
+
+	/// Owner and DNA of a kitty.
+	#[rpc(name = "kitties_kitty")]
+	fn kitty(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<BlockHash>,
+	) -> Result<Option<KittyInfo<AccountId, KittyIndex>>>;
+
+	/// Parents of a kitty.
+	#[rpc(name = "kitties_parents")]
+	fn parents(&self, kitty_id: KittyIndex, at: Option<BlockHash>) -> Result<Vec<KittyIndex>>;
+
+	/// Children of a kitty.
+	#[rpc(name = "kitties_children")]
+	fn children(&self, kitty_id: KittyIndex, at: Option<BlockHash>) -> Result<Vec<KittyIndex>>;
+
+	/// Kitties a kitty has been bred with.
+	#[rpc(name = "kitties_breedingPartners")]
+	fn breeding_partners(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<BlockHash>,
+	) -> Result<Vec<KittyIndex>>;
+
+	/// Full lineage of a kitty in one call.
+	#[rpc(name = "kitties_lineage")]
+	fn lineage(&self, kitty_id: KittyIndex, at: Option<BlockHash>) -> Result<KittyLineage<KittyIndex>>;
+
+	/// Number of kitties ever created.
+	#[rpc(name = "kitties_count")]
+	fn count(&self, at: Option<BlockHash>) -> Result<KittyIndex>;
+}
+
+/// A struct that implements the `KittiesApi`.
+pub struct Kitties<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Kitties<C, B> {
+	/// Create new `Kitties` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Kitties { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Unable to query kitties.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId, KittyIndex> KittiesApi<<Block as BlockT>::Hash, AccountId, KittyIndex>
+	for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: KittiesRuntimeApi<Block, AccountId, KittyIndex>,
+	AccountId: Codec,
+	KittyIndex: Codec + Copy,
+{
+	fn kitties_of(
+		&self,
+		account: AccountId,
+		start: u32,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<KittyIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.kitties_of(&at, account, start, limit).map_err(runtime_error)
+	}
+
+	fn owned_count(
+		&self,
+		account: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<u32> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.owned_kitties_count(&at, account).map_err(runtime_error)
+	}
+
+	fn kitty(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<KittyInfo<AccountId, KittyIndex>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let owner = api.kitty_owner(&at, kitty_id).map_err(runtime_error)?;
+		let dna = api.kitty_dna(&at, kitty_id).map_err(runtime_error)?;
+
+		Ok(owner.map(|owner| KittyInfo {
+			id: kitty_id,
+			owner,
+			dna: dna.map(|dna| dna.to_vec().into()),
+		}))
+	}
+
+	fn parents(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<KittyIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.parents(&at, kitty_id).map_err(runtime_error)
+	}
+
+	fn children(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<KittyIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.children(&at, kitty_id).map_err(runtime_error)
+	}
+
+	fn breeding_partners(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<KittyIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.breeding_partners(&at, kitty_id).map_err(runtime_error)
+	}
+
+	fn lineage(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<KittyLineage<KittyIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		Ok(KittyLineage {
+			parents: api.parents(&at, kitty_id).map_err(runtime_error)?,
+			children: api.children(&at, kitty_id).map_err(runtime_error)?,
+			breeding_partners: api.breeding_partners(&at, kitty_id).map_err(runtime_error)?,
+		})
+	}
+
+	fn count(&self, at: Option<<Block as BlockT>::Hash>) -> Result<KittyIndex> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.kitties_count(&at).map_err(runtime_error)
+	}
+}
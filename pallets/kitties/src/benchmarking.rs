@@ -0,0 +1,378 @@
+//! Benchmarks for pallet_kitties.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{benchmarks, account};
+use frame_system::RawOrigin;
+use frame_support::traits::OnFinalize;
+use sp_runtime::traits::Bounded;
+
+const SEED: u32 = 0;
+/// Kitties the caller already owns before the benchmarked call, unless
+/// `MaxKittiesPerAccount` is lower.
+const OWNED: u32 = 100;
+
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	fund::<T>(&who);
+	who
+}
+
+fn fund<T: Trait>(who: &T::AccountId) {
+	let balance = BalanceOf::<T>::max_value() / 4u32.into();
+	T::Currency::make_free_balance_be(who, balance);
+}
+
+/// Kitties to give an account so that it can still receive one more.
+fn owned<T: Trait>() -> u32 {
+	OWNED.min(T::MaxKittiesPerAccount::get().saturating_sub(1))
+}
+
+/// Hatch every egg, as if a round of commitments made after this block had closed.
+fn hatch_all<T: Trait>() {
+	let since = frame_system::Module::<T>::block_number().saturating_add(One::one());
+	while Module::<T>::next_to_hatch() < Module::<T>::kitties_count() {
+		Module::<T>::hatch_eggs(Default::default(), since);
+	}
+}
+
+/// A hatched kitty.
+fn create_kitty<T: Trait>(owner: &T::AccountId) -> T::KittyIndex {
+	let kitty_id = Module::<T>::kitties_count();
+	Module::<T>::create(RawOrigin::Signed(owner.clone()).into()).expect("owner is funded");
+	hatch_all::<T>();
+	kitty_id
+}
+
+fn create_kitties<T: Trait>(owner: &T::AccountId, count: u32) {
+	for _ in 0..count {
+		create_kitty::<T>(owner);
+	}
+}
+
+/// Move past the longest possible breeding cooldown.
+fn skip_cooldown<T: Trait>() {
+	let longest = T::BreedCooldown::get()
+		.saturating_mul((genome::MAX_COOLDOWN_INDEX as u32 + 1).into());
+	let now = frame_system::Module::<T>::block_number();
+	frame_system::Module::<T>::set_block_number(now.saturating_add(longest));
+}
+
+/// A kitty with a full ancestry tree of `depth` generations, none of them related to any
+/// kitty created before.
+fn family<T: Trait>(owner: &T::AccountId, depth: u32) -> T::KittyIndex {
+	if depth == 0 {
+		return create_kitty::<T>(owner);
+	}
+	let kitty_id_1 = family::<T>(owner, depth - 1);
+	let kitty_id_2 = family::<T>(owner, depth - 1);
+	skip_cooldown::<T>();
+	let kitty_id = Module::<T>::do_breed(owner, kitty_id_1, kitty_id_2).expect("parents are unrelated");
+	hatch_all::<T>();
+	kitty_id
+}
+
+benchmarks! {
+	_ { }
+
+	create {
+		let caller = funded_account::<T>("caller", 0);
+		create_kitties::<T>(&caller, owned::<T>());
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Module::<T>::owned_kitties_count(&caller), owned::<T>() + 1);
+	}
+
+	transfer {
+		let caller = funded_account::<T>("caller", 0);
+		let to = funded_account::<T>("to", 0);
+		create_kitties::<T>(&caller, owned::<T>());
+		create_kitties::<T>(&to, owned::<T>());
+		let kitty_id = create_kitty::<T>(&caller);
+	}: _(RawOrigin::Signed(caller), to.clone(), kitty_id)
+	verify {
+		assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(to));
+	}
+
+	create_batch {
+		let n in 1 .. T::MaxBatchSize::get().min(T::MaxKittiesPerAccount::get()).min(T::MaxEggsPerAccount::get());
+		let caller = funded_account::<T>("caller", 0);
+	}: _(RawOrigin::Signed(caller.clone()), n)
+	verify {
+		assert_eq!(Module::<T>::owned_kitties_count(&caller), n);
+	}
+
+	transfer_batch {
+		let n in 1 .. T::MaxBatchSize::get().min(T::MaxKittiesPerAccount::get());
+		let caller = funded_account::<T>("caller", 0);
+		let transfers = (0..n).map(|i| {
+			let to = funded_account::<T>("to", i);
+			(to, create_kitty::<T>(&caller))
+		}).collect::<Vec<_>>();
+	}: _(RawOrigin::Signed(caller.clone()), transfers)
+	verify {
+		assert_eq!(Module::<T>::owned_kitties_count(&caller), 0);
+	}
+
+	breed {
+		let caller = funded_account::<T>("caller", 0);
+		let d in 1 .. T::MaxInbreedingDepth::get().max(1);
+		let kitty_id_1 = family::<T>(&caller, d);
+		let kitty_id_2 = family::<T>(&caller, d);
+		skip_cooldown::<T>();
+		let kitty_id = Module::<T>::kitties_count();
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id_1, kitty_id_2)
+	verify {
+		assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(caller));
+	}
+
+	set_sire_price {
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitty::<T>(&caller);
+		let price = T::KittyReserve::get();
+	}: _(RawOrigin::Signed(caller), kitty_id, Some(price))
+	verify {
+		assert_eq!(Module::<T>::sire_price(kitty_id), Some(price));
+	}
+
+	breed_with_sire {
+		let caller = funded_account::<T>("caller", 0);
+		let sire_owner = funded_account::<T>("sire_owner", 0);
+		let d in 1 .. T::MaxInbreedingDepth::get().max(1);
+		let kitty_id_1 = family::<T>(&caller, d);
+		let sire_id = family::<T>(&sire_owner, d);
+		skip_cooldown::<T>();
+		let price = T::KittyReserve::get();
+		Module::<T>::set_sire_price(RawOrigin::Signed(sire_owner).into(), sire_id, Some(price))?;
+		let kitty_id = Module::<T>::kitties_count();
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id_1, sire_id, price)
+	verify {
+		assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(caller));
+	}
+
+	ask {
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitty::<T>(&caller);
+		let price = T::KittyReserve::get();
+	}: _(RawOrigin::Signed(caller), kitty_id, Some(price))
+	verify {
+		assert_eq!(Module::<T>::kitty_price(kitty_id), Some(price));
+	}
+
+	buy {
+		let seller = funded_account::<T>("seller", 0);
+		let buyer = funded_account::<T>("buyer", 0);
+		let kitty_id = create_kitty::<T>(&seller);
+		let price = T::KittyReserve::get();
+		Module::<T>::ask(RawOrigin::Signed(seller).into(), kitty_id, Some(price))?;
+	}: _(RawOrigin::Signed(buyer.clone()), kitty_id, price)
+	verify {
+		assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(buyer));
+	}
+
+	approve {
+		let caller = funded_account::<T>("caller", 0);
+		let operator = funded_account::<T>("operator", 0);
+		let spender: T::AccountId = account("spender", 0, SEED);
+		let kitty_id = create_kitty::<T>(&caller);
+		Module::<T>::set_approval_for_all(RawOrigin::Signed(caller).into(), operator.clone(), true)?;
+	}: _(RawOrigin::Signed(operator), kitty_id, Some(spender.clone()))
+	verify {
+		assert_eq!(Module::<T>::approved(kitty_id), Some(spender));
+	}
+
+	set_approval_for_all {
+		let caller = funded_account::<T>("caller", 0);
+		let operator: T::AccountId = account("operator", 0, SEED);
+	}: _(RawOrigin::Signed(caller.clone()), operator.clone(), true)
+	verify {
+		assert!(Module::<T>::is_operator(caller, operator));
+	}
+
+	transfer_from {
+		let owner = funded_account::<T>("owner", 0);
+		let operator = funded_account::<T>("operator", 0);
+		let to = funded_account::<T>("to", 0);
+		create_kitties::<T>(&owner, owned::<T>());
+		let kitty_id = create_kitty::<T>(&owner);
+		// An operator is checked after the single kitty approval, so it is the slowest path.
+		Module::<T>::set_approval_for_all(RawOrigin::Signed(owner.clone()).into(), operator.clone(), true)?;
+	}: _(RawOrigin::Signed(operator), owner, to.clone(), kitty_id)
+	verify {
+		assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(to));
+	}
+
+	burn {
+		let caller = funded_account::<T>("caller", 0);
+		create_kitties::<T>(&caller, owned::<T>());
+		let kitty_id = create_kitty::<T>(&caller);
+	}: _(RawOrigin::Signed(caller), kitty_id)
+	verify {
+		assert!(Module::<T>::kitties(kitty_id).is_none());
+	}
+
+	force_burn {
+		let owner = funded_account::<T>("owner", 0);
+		create_kitties::<T>(&owner, owned::<T>());
+		let kitty_id = create_kitty::<T>(&owner);
+	}: _(RawOrigin::Root, kitty_id)
+	verify {
+		assert!(Module::<T>::kitties(kitty_id).is_none());
+	}
+
+	accept_breeding_offer {
+		let caller = funded_account::<T>("caller", 0);
+		let sire_owner = T::OfferSigner::signer();
+		fund::<T>(&sire_owner);
+		let d in 1 .. T::MaxInbreedingDepth::get().max(1);
+		let kitty_id_1 = family::<T>(&caller, d);
+		let sire_id = family::<T>(&sire_owner, d);
+		skip_cooldown::<T>();
+		let offer = BreedingOffer {
+			owner: sire_owner.clone(),
+			kitty_id: sire_id,
+			price: T::KittyReserve::get(),
+			expiry: frame_system::Module::<T>::block_number(),
+			nonce: 0,
+		};
+		let signature = T::OfferSigner::sign(&sire_owner, &Module::<T>::breeding_offer_payload(&offer));
+		let kitty_id = Module::<T>::kitties_count();
+	}: _(RawOrigin::Signed(caller.clone()), kitty_id_1, offer, signature)
+	verify {
+		assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(caller));
+	}
+
+	cancel_breeding_offer {
+		let caller = funded_account::<T>("caller", 0);
+	}: _(RawOrigin::Signed(caller.clone()), 0)
+	verify {
+		assert!(Module::<T>::offer_nonce_used(&caller, 0));
+	}
+
+	set_metadata {
+		let n in 0 .. T::MaxNameLength::get();
+		let u in 0 .. T::MaxUriLength::get();
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitty::<T>(&caller);
+		let name = vec![b'n'; n as usize];
+		let uri = vec![b'u'; u as usize];
+	}: _(RawOrigin::Signed(caller), kitty_id, name.clone(), uri)
+	verify {
+		assert_eq!(Module::<T>::kitty_metadata(kitty_id).map(|m| m.name), Some(name));
+	}
+
+	clear_metadata {
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitty::<T>(&caller);
+		let name = vec![b'n'; T::MaxNameLength::get() as usize];
+		let uri = vec![b'u'; T::MaxUriLength::get() as usize];
+		Module::<T>::set_metadata(RawOrigin::Signed(caller.clone()).into(), kitty_id, name, uri)?;
+	}: _(RawOrigin::Signed(caller), kitty_id)
+	verify {
+		assert!(Module::<T>::kitty_metadata(kitty_id).is_none());
+	}
+
+	start_auction {
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id = create_kitty::<T>(&caller);
+		Module::<T>::ask(RawOrigin::Signed(caller.clone()).into(), kitty_id, Some(T::KittyReserve::get()))?;
+		let duration = T::MaxAuctionDuration::get();
+	}: _(RawOrigin::Signed(caller), kitty_id, T::KittyReserve::get(), duration)
+	verify {
+		assert!(Module::<T>::auction(kitty_id).is_some());
+	}
+
+	bid {
+		let seller = funded_account::<T>("seller", 0);
+		let outbid = funded_account::<T>("bidder", 0);
+		let bidder = funded_account::<T>("bidder", 1);
+		let kitty_id = create_kitty::<T>(&seller);
+		let reserve = T::KittyReserve::get();
+		let duration = T::MaxAuctionDuration::get();
+		Module::<T>::start_auction(RawOrigin::Signed(seller).into(), kitty_id, reserve, duration)?;
+		// Outbidding releases the previous highest bid, which is the slowest path.
+		Module::<T>::bid(RawOrigin::Signed(outbid).into(), kitty_id, reserve)?;
+		let amount = reserve.saturating_add(reserve);
+	}: _(RawOrigin::Signed(bidder.clone()), kitty_id, amount)
+	verify {
+		assert_eq!(Module::<T>::auction(kitty_id).and_then(|a| a.best_bid), Some((bidder, amount)));
+	}
+
+	settle_auctions {
+		let a in 0 .. T::MaxAuctionsEndingPerBlock::get();
+		let reserve = T::KittyReserve::get();
+		let duration = T::MaxAuctionDuration::get();
+		let end = frame_system::Module::<T>::block_number().saturating_add(duration);
+		// Every auction has its own seller and bidder, so that `MaxKittiesPerAccount` does not
+		// bound `a`.
+		for i in 0..a {
+			let seller = funded_account::<T>("seller", i);
+			let bidder = funded_account::<T>("bidder", i);
+			let kitty_id = create_kitty::<T>(&seller);
+			Module::<T>::start_auction(RawOrigin::Signed(seller).into(), kitty_id, reserve, duration)?;
+			Module::<T>::bid(RawOrigin::Signed(bidder).into(), kitty_id, reserve)?;
+		}
+	}: {
+		Module::<T>::on_finalize(end);
+	}
+	verify {
+		for i in 0..a {
+			assert_eq!(Module::<T>::owned_kitties_count(&account::<T::AccountId>("bidder", i, SEED)), 1);
+		}
+	}
+
+	hatch_eggs {
+		let n in 0 .. T::MaxHatchPerBlock::get();
+		// Bred kitties are the costlier ones to hatch.
+		let caller = funded_account::<T>("caller", 0);
+		let kitty_id_1 = create_kitty::<T>(&caller);
+		let kitty_id_2 = create_kitty::<T>(&caller);
+		for i in 0..n {
+			let owner = funded_account::<T>("owner", i);
+			skip_cooldown::<T>();
+			Module::<T>::do_breed(&owner, kitty_id_1, kitty_id_2).expect("parents are hatched");
+		}
+		let since = frame_system::Module::<T>::block_number().saturating_add(One::one());
+	}: {
+		Module::<T>::hatch_eggs(Default::default(), since);
+	}
+	verify {
+		assert_eq!(Module::<T>::next_to_hatch(), Module::<T>::kitties_count());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_create::<Test>());
+			assert_ok!(test_benchmark_transfer::<Test>());
+			assert_ok!(test_benchmark_create_batch::<Test>());
+			assert_ok!(test_benchmark_transfer_batch::<Test>());
+			assert_ok!(test_benchmark_breed::<Test>());
+			assert_ok!(test_benchmark_set_sire_price::<Test>());
+			assert_ok!(test_benchmark_breed_with_sire::<Test>());
+			assert_ok!(test_benchmark_ask::<Test>());
+			assert_ok!(test_benchmark_buy::<Test>());
+			assert_ok!(test_benchmark_approve::<Test>());
+			assert_ok!(test_benchmark_set_approval_for_all::<Test>());
+			assert_ok!(test_benchmark_transfer_from::<Test>());
+			assert_ok!(test_benchmark_burn::<Test>());
+			assert_ok!(test_benchmark_force_burn::<Test>());
+			assert_ok!(test_benchmark_accept_breeding_offer::<Test>());
+			assert_ok!(test_benchmark_cancel_breeding_offer::<Test>());
+			assert_ok!(test_benchmark_set_metadata::<Test>());
+			assert_ok!(test_benchmark_clear_metadata::<Test>());
+			assert_ok!(test_benchmark_start_auction::<Test>());
+			assert_ok!(test_benchmark_bid::<Test>());
+			assert_ok!(test_benchmark_settle_auctions::<Test>());
+			assert_ok!(test_benchmark_hatch_eggs::<Test>());
+		});
+	}
+}
@@ -0,0 +1,175 @@
+//! Interpretation of the 16 byte kitty DNA as named genes.
+//!
+//! Every visible trait is carried by a pair of alleles, one inherited from each parent. The
+//! variants of a trait are declared from most to least dominant, so the expressed trait is the
+//! more dominant of the two alleles and the other one is carried silently, ready to surface in
+//! a later generation.
+//!
+//! DNA layout:
+//!
+//! | bytes   | gene                                            |
+//! |---------|-------------------------------------------------|
+//! | 0, 1    | body colour (expressed allele, hidden allele)   |
+//! | 2, 3    | eye shape (expressed allele, hidden allele)     |
+//! | 4, 5    | pattern (expressed allele, hidden allele)       |
+//! | 6, 7    | generation, big endian                          |
+//! | 8       | cooldown index                                  |
+//! | 9 .. 16 | free entropy, mixed bitwise on breeding         |
+
+use codec::{Encode, Decode};
+use sp_runtime::RuntimeDebug;
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+
+const BODY_COLOUR: usize = 0;
+const EYE_SHAPE: usize = 2;
+const PATTERN: usize = 4;
+const GENERATION: usize = 6;
+const COOLDOWN_INDEX: usize = 8;
+const FREE: usize = 9;
+
+/// Cooldown index reached by the slowest breeders.
+pub const MAX_COOLDOWN_INDEX: u8 = 13;
+
+/// A gene whose variants are ordered from most to least dominant.
+pub trait Gene: Sized {
+	/// Number of variants.
+	const VARIANTS: u8;
+
+	/// The variant at `index`, which is always below `VARIANTS`.
+	fn from_index(index: u8) -> Self;
+
+	/// The variant an allele codes for.
+	fn from_allele(allele: u8) -> Self {
+		Self::from_index(allele % Self::VARIANTS)
+	}
+
+	/// The variant expressed by a pair of alleles.
+	fn express(a: u8, b: u8) -> Self {
+		Self::from_index((a % Self::VARIANTS).min(b % Self::VARIANTS))
+	}
+}
+
+macro_rules! count {
+	() => (0u8);
+	($x:ident $($xs:ident)*) => (1u8 + count!($($xs)*));
+}
+
+macro_rules! gene {
+	($(#[$attr:meta])* $name:ident { $($variant:ident),+ $(,)? }) => {
+		$(#[$attr])*
+		#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+		#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+		pub enum $name {
+			$($variant),+
+		}
+
+		impl Gene for $name {
+			const VARIANTS: u8 = count!($($variant)+);
+
+			fn from_index(index: u8) -> Self {
+				let variants = [$($name::$variant),+];
+				variants[index as usize % variants.len()]
+			}
+		}
+	};
+}
+
+gene!(
+	/// Colour of the fur, most dominant first.
+	BodyColour { Black, Ginger, Grey, Chocolate, Cinnamon, Cream, Lilac, White }
+);
+
+gene!(
+	/// Shape of the eyes, most dominant first.
+	EyeShape { Round, Almond, Slanted, Sleepy }
+);
+
+gene!(
+	/// Markings on the fur, most dominant first.
+	Pattern { Tabby, Spotted, Tortoiseshell, Calico, Pointed, Solid }
+);
+
+/// The traits a kitty's DNA decodes to.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct KittyTraits {
+	pub body_colour: BodyColour,
+	pub eye_shape: EyeShape,
+	pub pattern: Pattern,
+	/// Zero for created kitties, one more than the older parent for bred ones.
+	pub generation: u16,
+	/// How slowly the kitty recovers after breeding.
+	pub cooldown_index: u8,
+}
+
+/// Decode the traits carried by `dna`.
+pub fn decode(dna: &[u8; 16]) -> KittyTraits {
+	KittyTraits {
+		body_colour: BodyColour::express(dna[BODY_COLOUR], dna[BODY_COLOUR + 1]),
+		eye_shape: EyeShape::express(dna[EYE_SHAPE], dna[EYE_SHAPE + 1]),
+		pattern: Pattern::express(dna[PATTERN], dna[PATTERN + 1]),
+		generation: generation(dna),
+		cooldown_index: dna[COOLDOWN_INDEX],
+	}
+}
+
+/// The generation recorded in `dna`.
+pub fn generation(dna: &[u8; 16]) -> u16 {
+	u16::from_be_bytes([dna[GENERATION], dna[GENERATION + 1]])
+}
+
+/// Cooldown index of a kitty of the given generation.
+pub fn cooldown_index(generation: u16) -> u8 {
+	(generation / 2).min(MAX_COOLDOWN_INDEX as u16) as u8
+}
+
+/// Record `generation`, and the cooldown index that goes with it, in `dna`.
+pub fn set_generation(dna: &mut [u8; 16], generation: u16) {
+	dna[GENERATION..GENERATION + 2].copy_from_slice(&generation.to_be_bytes());
+	dna[COOLDOWN_INDEX] = cooldown_index(generation);
+}
+
+/// DNA of a first generation kitty built from random bytes.
+pub fn new_dna(random: [u8; 16]) -> [u8; 16] {
+	let mut dna = random;
+	set_generation(&mut dna, 0);
+	dna
+}
+
+/// Take the bits of `dna1` where `selector` is set and the bits of `dna2` elsewhere.
+pub fn combine_dna(dna1: u8, dna2: u8, selector: u8) -> u8 {
+	(selector & dna1) | (!selector & dna2)
+}
+
+/// Pick one allele of the pair at `offset` in each parent and order them so that the
+/// expressed allele comes first.
+fn inherit<G: Gene + Ord>(dna1: &[u8; 16], dna2: &[u8; 16], offset: usize, selector: u8) -> [u8; 2] {
+	let a = dna1[offset + (selector & 1) as usize];
+	let b = dna2[offset + ((selector >> 1) & 1) as usize];
+	if G::from_allele(b) < G::from_allele(a) { [b, a] } else { [a, b] }
+}
+
+/// DNA of a kitty bred from `dna1` and `dna2`, with `selector` supplying the randomness.
+///
+/// Each trait takes one allele from each parent, the generation is one more than the older
+/// parent's, and the free bytes are mixed bitwise with `combine_dna`.
+pub fn breed(dna1: &[u8; 16], dna2: &[u8; 16], selector: &[u8; 16]) -> [u8; 16] {
+	let mut dna = [0u8; 16];
+
+	dna[BODY_COLOUR..BODY_COLOUR + 2]
+		.copy_from_slice(&inherit::<BodyColour>(dna1, dna2, BODY_COLOUR, selector[BODY_COLOUR]));
+	dna[EYE_SHAPE..EYE_SHAPE + 2]
+		.copy_from_slice(&inherit::<EyeShape>(dna1, dna2, EYE_SHAPE, selector[EYE_SHAPE]));
+	dna[PATTERN..PATTERN + 2]
+		.copy_from_slice(&inherit::<Pattern>(dna1, dna2, PATTERN, selector[PATTERN]));
+
+	let generation = generation(dna1).max(generation(dna2)).saturating_add(1);
+	set_generation(&mut dna, generation);
+
+	for i in FREE..dna.len() {
+		dna[i] = combine_dna(dna1[i], dna2[i], selector[i]);
+	}
+
+	dna
+}
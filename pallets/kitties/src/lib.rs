@@ -2,25 +2,203 @@
 
 use codec::{Encode, Decode};
 use frame_support::{decl_module, decl_storage, decl_event, decl_error, ensure, StorageValue, StorageMap,
-	traits::Randomness,
+	StorageDoubleMap, Parameter, transactional,
+	weights::Weight,
+	traits::{Currency, ReservableCurrency, ExistenceRequirement, BalanceStatus, Get},
 };
+use pallet_commit_reveal::FreshRandomness;
 use sp_runtime::{
-	DispatchError, traits::{
-		AtLeast32Bit, Member, MaybeSerialize, MaybeDisplay,
+	DispatchError, DispatchResult, RuntimeDebug, traits::{
+		AtLeast32BitUnsigned, Member, MaybeSerialize, MaybeDisplay, One, Zero, CheckedAdd, Saturating,
+		Verify, IdentifyAccount,
 	},
 };
 use sp_io::hashing::blake2_128;
-use frame_system::ensure_signed;
-use sp_std::prelude::*;
+use frame_system::{ensure_signed, ensure_root};
+use sp_std::{prelude::*, collections::btree_set::BTreeSet};
 
-type KittyIndex = u32;
-#[derive(Encode, Decode)]
-pub struct Kitty(pub [u8; 16]);
+pub mod genome;
+pub mod weights;
+mod migration;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub use weights::WeightInfo;
+
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct Kitty<BlockNumber, Balance> {
+	pub dna: [u8; 16],
+	/// Zero for created kitties, one more than the older parent for bred ones.
+	pub generation: u16,
+	/// First block at which the kitty may breed again.
+	pub next_breed_block: BlockNumber,
+	/// Reserved from the owner and moving along with the kitty. `KittyReserve` at the time the
+	/// kitty was made, or less for kitties migrated from owners who could not cover it.
+	pub deposit: Balance,
+}
+
+impl<BlockNumber, Balance> Kitty<BlockNumber, Balance> {
+	/// Decode the genes carried by this kitty's DNA.
+	pub fn traits(&self) -> genome::KittyTraits {
+		genome::decode(&self.dna)
+	}
+}
+
+type KittyOf<T> = Kitty<<T as frame_system::Trait>::BlockNumber, BalanceOf<T>>;
+
+/// A kitty whose DNA is not drawn yet.
+///
+/// DNA is drawn from randomness made of commitments that came after the kitty was made, so
+/// that neither its owner nor the block author can know it when creating or breeding.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct Egg<AccountId, BlockNumber> {
+	/// Account that created or bred the kitty. Its eggs count towards `MaxEggsPerAccount`
+	/// until they hatch, whoever owns them by then.
+	pub laid_by: AccountId,
+	/// Block in which the egg was laid. It hatches once `T::Randomness` is made of
+	/// commitments from later blocks only.
+	pub laid_at: BlockNumber,
+	/// DNA of the two parents of a bred kitty, `None` for a created one.
+	pub parents: Option<([u8; 16], [u8; 16])>,
+}
+
+type EggOf<T> = Egg<<T as frame_system::Trait>::AccountId, <T as frame_system::Trait>::BlockNumber>;
+
+/// Subject under which kitties ask `T::Randomness` for the value they hatch from.
+const HATCH_SUBJECT: &[u8] = b"kitties/hatch";
+
+/// An English auction of a kitty.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct Auction<AccountId, Balance, BlockNumber> {
+	pub seller: AccountId,
+	/// Lowest acceptable bid.
+	pub reserve: Balance,
+	/// Block at the end of which the auction is settled.
+	pub end: BlockNumber,
+	/// Highest bid so far. Its amount is reserved from the bidder.
+	pub best_bid: Option<(AccountId, Balance)>,
+}
+
+type AuctionOf<T> = Auction<
+	<T as frame_system::Trait>::AccountId,
+	BalanceOf<T>,
+	<T as frame_system::Trait>::BlockNumber,
+>;
+
+/// Name and image of a kitty, set by its owner.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct Metadata<Balance> {
+	pub name: Vec<u8>,
+	pub uri: Vec<u8>,
+	/// Reserved from the owner and moving along with the kitty, like its `KittyReserve`.
+	pub deposit: Balance,
+}
+
+type MetadataOf<T> = Metadata<BalanceOf<T>>;
+
+/// An offer to breed with the kitty of `owner`, signed by `owner` off-chain and accepted on-chain
+/// by anyone with a kitty to breed.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct BreedingOffer<AccountId, KittyIndex, Balance, BlockNumber> {
+	pub owner: AccountId,
+	pub kitty_id: KittyIndex,
+	/// Paid to `owner` by the account accepting the offer.
+	pub price: Balance,
+	/// Last block in which the offer may be accepted.
+	pub expiry: BlockNumber,
+	/// Chosen by `owner`, who must not use it for another offer. Marked as used in
+	/// `UsedOfferNonces` when the offer is accepted or cancelled, so that it cannot be replayed.
+	pub nonce: u32,
+}
+
+/// Makes real `OffchainSignature`s, which a benchmark cannot do for a generic signature type.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait OfferSigner<AccountId, Signature> {
+	/// An account whose key is at hand to sign with.
+	fn signer() -> AccountId;
+	/// Signature of `payload` by `signer`, which was made by `signer()`.
+	fn sign(signer: &AccountId, payload: &[u8]) -> Signature;
+}
+
+type BreedingOfferOf<T> = BreedingOffer<
+	<T as frame_system::Trait>::AccountId,
+	<T as Trait>::KittyIndex,
+	BalanceOf<T>,
+	<T as frame_system::Trait>::BlockNumber,
+>;
+
+/// Context signed along with a breeding offer, so that the signature cannot be passed off as
+/// one over something else.
+const BREEDING_OFFER_CONTEXT: &[u8] = b"kitties:breeding-offer";
+
+// A value placed in storage that represents the current version of the Kitties storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	/// Kitties stored as bare DNA.
+	V1_0_0,
+	/// Kitties stored as `Kitty` records with generation and cooldown.
+	V2_0_0,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1_0_0
+	}
+}
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
 
 pub trait Trait: frame_system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
-	type Randomness: Randomness<Self::Hash>;
-	type KittyIndex: Clone + Eq + Member + MaybeSerialize + Default + MaybeDisplay + AtLeast32Bit
+	/// Source of the DNA of new kitties. Kitties hatch at the start of the first block in which
+	/// its output for `HATCH_SUBJECT` is made of commitments from after their egg was laid.
+	type Randomness: FreshRandomness<Self::Hash, Self::BlockNumber>;
+	/// The currency in which kitties are bought and sold.
+	type Currency: ReservableCurrency<Self::AccountId>;
+	/// Deposit reserved from the owner for every kitty it holds. It is reserved when a kitty
+	/// is created or bred and moves along with the kitty when it changes hands.
+	type KittyReserve: Get<BalanceOf<Self>>;
+	/// Blocks a kitty has to rest after breeding, multiplied by one more than its
+	/// cooldown index so that later generations recover more slowly.
+	type BreedCooldown: Get<Self::BlockNumber>;
+	/// How many generations back to look for shared ancestors when breeding. Parent/child and
+	/// sibling pairs are always rejected, even when this is zero. Breeding reads up to
+	/// `2^(depth + 1) - 2` ancestors of each parent, so its weight doubles with every generation.
+	type MaxInbreedingDepth: Get<u32>;
+	/// Longest an auction may run, in blocks.
+	type MaxAuctionDuration: Get<Self::BlockNumber>;
+	/// Most auctions that may end in the same block. All of them are settled at the end of
+	/// that block, so this bounds the weight of settling them.
+	type MaxAuctionsEndingPerBlock: Get<u32>;
+	/// Most kitties a single account may own.
+	type MaxKittiesPerAccount: Get<u32>;
+	/// Longest kitty name, in bytes.
+	type MaxNameLength: Get<u32>;
+	/// Longest kitty image URI, in bytes.
+	type MaxUriLength: Get<u32>;
+	/// Deposit reserved for every byte of a kitty's name and URI.
+	type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+	/// Most kitties created or transferred by a single batch call.
+	type MaxBatchSize: Get<u32>;
+	/// Most kitties laid by a single account that may wait to hatch at once.
+	type MaxEggsPerAccount: Get<u32>;
+	/// Most eggs looked at in a block. Eggs ready to hatch beyond it hatch in later blocks, so
+	/// this bounds the weight of hatching them.
+	type MaxHatchPerBlock: Get<u32>;
+	/// Signature over `Module::breeding_offer_payload` of a `BreedingOffer`.
+	type OffchainSignature: Verify<Signer = Self::OffchainPublic> + Parameter;
+	/// Key signing breeding offers, identifying the account of the offering owner.
+	type OffchainPublic: IdentifyAccount<AccountId = Self::AccountId>;
+	/// Signs breeding offers for the `accept_breeding_offer` benchmark.
+	#[cfg(feature = "runtime-benchmarks")]
+	type OfferSigner: OfferSigner<Self::AccountId, Self::OffchainSignature>;
+	/// Weight information for the extrinsics of this pallet.
+	type WeightInfo: WeightInfo;
+	/// The type used to identify kitties. Every storage item and event is keyed by it.
+	type KittyIndex: Parameter + Member + MaybeSerialize + Default + MaybeDisplay + AtLeast32BitUnsigned
 	+ Copy;
 }
 
@@ -31,22 +209,116 @@ decl_storage! {
 	// This name may be updated, but each pallet in the runtime must use a unique name.
 	// ---------------------------------vvvvvvvvvvvvvv
 	trait Store for Module<T: Trait> as Kitties {
-		pub Kitties get(fn kitties):map hasher(blake2_128_concat) KittyIndex => Option<Kitty>;
-		pub KittiesCount get(fn kitties_count): KittyIndex;
-		pub KittyOwners get(fn kitty_owner): map hasher(blake2_128_concat) KittyIndex => Option<T::AccountId>;
-		pub UserKitties get(fn user_kitties): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) KittyIndex => KittyIndex;
-		pub Parents get(fn parents):double_map hasher(blake2_128_concat) KittyIndex, hasher(blake2_128_concat) KittyIndex => KittyIndex;
-		pub Children get(fn children):double_map hasher(blake2_128_concat) KittyIndex, hasher(blake2_128_concat) KittyIndex => KittyIndex;
-		pub Breeded get(fn breeded):double_map hasher(blake2_128_concat) KittyIndex, hasher(blake2_128_concat) KittyIndex => KittyIndex;
+		pub Kitties get(fn kitties):map hasher(blake2_128_concat) T::KittyIndex => Option<KittyOf<T>>;
+		pub KittiesCount get(fn kitties_count): T::KittyIndex;
+		pub KittyOwners get(fn kitty_owner): map hasher(blake2_128_concat) T::KittyIndex => Option<T::AccountId>;
+		pub UserKitties get(fn user_kitties): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::KittyIndex => T::KittyIndex;
+		/// Number of kitties owned by an account, bounded by `MaxKittiesPerAccount`.
+		pub OwnedKittiesCount get(fn owned_kitties_count): map hasher(blake2_128_concat) T::AccountId => u32;
+		pub Parents get(fn parents):double_map hasher(blake2_128_concat) T::KittyIndex, hasher(blake2_128_concat) T::KittyIndex => T::KittyIndex;
+		pub Children get(fn children):double_map hasher(blake2_128_concat) T::KittyIndex, hasher(blake2_128_concat) T::KittyIndex => T::KittyIndex;
+		pub Breeded get(fn breeded):double_map hasher(blake2_128_concat) T::KittyIndex, hasher(blake2_128_concat) T::KittyIndex => T::KittyIndex;
+		/// Asking price of kitties listed for sale by their owner.
+		pub KittyPrices get(fn kitty_price): map hasher(blake2_128_concat) T::KittyIndex => Option<BalanceOf<T>>;
+		/// Account allowed to transfer a single kitty on behalf of its owner.
+		pub Approvals get(fn approved): map hasher(blake2_128_concat) T::KittyIndex => Option<T::AccountId>;
+		/// Operators allowed to transfer every kitty of an owner. (owner, operator)
+		pub Operators get(fn is_operator): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::AccountId => bool;
+		/// Running auctions. A kitty under auction cannot be transferred, bred, listed or burnt.
+		pub Auctions get(fn auction): map hasher(blake2_128_concat) T::KittyIndex => Option<AuctionOf<T>>;
+		/// Auctions to settle at the end of a block. (end block, kitty)
+		pub AuctionsEnding get(fn auctions_ending): double_map hasher(twox_64_concat) T::BlockNumber, hasher(blake2_128_concat) T::KittyIndex => T::KittyIndex;
+		/// Number of auctions ending at a block, bounded by `MaxAuctionsEndingPerBlock`.
+		pub AuctionsEndingCount get(fn auctions_ending_count): map hasher(twox_64_concat) T::BlockNumber => u32;
+		/// Fee asked by owners for breeding with their kitty as a sire.
+		pub SirePrices get(fn sire_price): map hasher(blake2_128_concat) T::KittyIndex => Option<BalanceOf<T>>;
+		/// Nonces of breeding offers that were accepted or cancelled, by owner.
+		pub UsedOfferNonces get(fn offer_nonce_used): double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) u32 => bool;
+		/// Kitties whose DNA is not drawn yet. Kitty indices grow with the block an egg is laid
+		/// in, so eggs hatch in index order.
+		pub Eggs get(fn egg): map hasher(blake2_128_concat) T::KittyIndex => Option<EggOf<T>>;
+		/// Lowest kitty index that may still be an egg.
+		pub NextToHatch get(fn next_to_hatch): T::KittyIndex;
+		/// Number of eggs laid by an account that have not hatched yet, bounded by
+		/// `MaxEggsPerAccount`.
+		pub EggsOf get(fn eggs_of): map hasher(blake2_128_concat) T::AccountId => u32;
+		/// Name and image URI of kitties.
+		pub KittyMetadata get(fn kitty_metadata): map hasher(blake2_128_concat) T::KittyIndex => Option<MetadataOf<T>>;
+		/// Storage version of the pallet.
+		///
+		/// This is set to v2.0.0 for new networks.
+		StorageVersion build(|_: &GenesisConfig<T>| Releases::V2_0_0): Releases;
+	}
+	add_extra_genesis {
+		/// Kitties minted at genesis, as owner and DNA. Kitties without DNA get one derived from
+		/// their owner and index. Owners must be endowed to cover `KittyReserve`.
+		config(kitties): Vec<(T::AccountId, Option<[u8; 16]>)>;
+		build(|config: &GenesisConfig<T>| {
+			for (owner, dna) in config.kitties.iter() {
+				let kitty_id = <Module<T>>::next_kitty_id()
+					.expect("genesis kitties must fit in KittyIndex");
+				<Module<T>>::ensure_can_own(owner)
+					.expect("genesis kitties must not exceed MaxKittiesPerAccount");
+				let dna = dna.unwrap_or_else(|| (owner, kitty_id).using_encoded(blake2_128));
+				let deposit = T::KittyReserve::get();
+				T::Currency::reserve(owner, deposit)
+					.expect("genesis kitty owners must be able to reserve KittyReserve");
+				<Module<T>>::insert_kitty(owner, kitty_id, Kitty {
+					dna: genome::new_dna(dna),
+					generation: 0,
+					next_breed_block: Zero::zero(),
+					deposit,
+				});
+			}
+			// Genesis kitties are hatched already.
+			<NextToHatch<T>>::put(<Module<T>>::kitties_count());
+		});
 	}
 }
 
 // Pallets use events to inform users when important changes are made.
 // https://substrate.dev/docs/en/knowledgebase/runtime/events
 decl_event!(
-	pub enum Event<T> where <T as frame_system::Trait>::AccountId, {
+	pub enum Event<T> where
+		<T as frame_system::Trait>::AccountId,
+		<T as frame_system::Trait>::BlockNumber,
+		KittyIndex = <T as Trait>::KittyIndex,
+		Balance = BalanceOf<T>,
+	{
+		/// A kitty was created or bred. Its DNA is drawn when it hatches. (owner, kitty)
 		KittyCreated(AccountId, KittyIndex),
+		/// A kitty got its DNA. (kitty, dna)
+		KittyHatched(KittyIndex, [u8; 16]),
 		Transferred(AccountId, AccountId, KittyIndex),
+		/// Owner listed a kitty for sale at the given price, or delisted it with `None`.
+		PriceSet(AccountId, KittyIndex, Option<Balance>),
+		/// A kitty was sold. (seller, buyer, kitty, price)
+		Sold(AccountId, AccountId, KittyIndex, Balance),
+		/// A kitty was destroyed. (last owner, kitty)
+		KittyBurned(AccountId, KittyIndex),
+		/// The approved spender of a kitty was set, or cleared with `None`. (owner, spender, kitty)
+		Approval(AccountId, Option<AccountId>, KittyIndex),
+		/// An operator was granted or revoked access to all kitties of an owner.
+		/// (owner, operator, approved)
+		ApprovalForAll(AccountId, AccountId, bool),
+		/// An auction started. (seller, kitty, reserve, end block)
+		AuctionStarted(AccountId, KittyIndex, Balance, BlockNumber),
+		/// A bid became the highest one of an auction. (bidder, kitty, amount)
+		BidPlaced(AccountId, KittyIndex, Balance),
+		/// An auction ended with a winner. (seller, winner, kitty, price)
+		AuctionWon(AccountId, AccountId, KittyIndex, Balance),
+		/// An auction ended without any bid. (seller, kitty)
+		AuctionUnsold(AccountId, KittyIndex),
+		/// Owner offered a kitty as a sire for the given fee, or withdrew it with `None`.
+		SirePriceSet(AccountId, KittyIndex, Option<Balance>),
+		/// A kitty was bred with a rented sire. (renter, sire owner, sire, child, fee)
+		SireRented(AccountId, AccountId, KittyIndex, KittyIndex, Balance),
+		/// The name and image URI of a kitty were set. (owner, kitty)
+		MetadataSet(AccountId, KittyIndex),
+		/// The name and image URI of a kitty were removed. (owner, kitty)
+		MetadataCleared(AccountId, KittyIndex),
+		/// An owner cancelled its breeding offer before it was accepted. (owner, nonce)
+		BreedingOfferCancelled(AccountId, u32),
 	}
 );
 
@@ -56,59 +328,377 @@ decl_error! {
 		KittiesCountOverflow,
 		InvalidKittyId,
 		RequireDifferentParent,
+		KittyOnCooldown,
+		RelatedKitties,
+		NotKittyOwner,
+		TransferToSelf,
+		KittyNotForSale,
+		PriceTooLow,
+		BuyFromSelf,
+		NotApproved,
+		ApproveToSelf,
+		KittyInAuction,
+		NotInAuction,
+		InvalidAuctionDuration,
+		TooManyAuctionsEnding,
+		BidOnOwnAuction,
+		BidTooLow,
+		/// The winning bid of an auction is no longer reserved in full.
+		BidNotReserved,
+		SireNotForRent,
+		RentFromSelf,
+		TooManyKitties,
+		NameTooLong,
+		UriTooLong,
+		NoMetadata,
+		BatchTooLarge,
+		OfferExpired,
+		/// The nonce of the breeding offer was used by an accepted or cancelled offer.
+		InvalidOfferNonce,
+		InvalidSignature,
+		/// The account has too many kitties waiting to hatch.
+		TooManyEggs,
+		/// The kitty has not hatched yet.
+		KittyNotHatched,
 	}
 }
 
 impl<T: Trait> Module<T> {
-	pub fn insert_kitty(owner: &T::AccountId, kitty_id: KittyIndex, kitty: Kitty) {
-		Kitties::insert(kitty_id, kitty);
-		KittiesCount::put(kitty_id + 1);
+	pub fn insert_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex, kitty: KittyOf<T>) {
+		<Kitties<T>>::insert(kitty_id, kitty);
+		<KittiesCount<T>>::put(kitty_id.saturating_add(One::one()));
 		<KittyOwners<T>>::insert(kitty_id, owner);
 		<UserKitties<T>>::insert(owner, kitty_id, kitty_id);
+		<OwnedKittiesCount<T>>::mutate(owner, |count| *count = count.saturating_add(1));
+	}
+
+	/// Up to `limit` kitties owned by `owner`, skipping the first `start` of them.
+	///
+	/// Kitties are listed in storage order, which stays the same between calls as long as
+	/// `owner` neither gains nor loses kitties, so clients can page through them.
+	pub fn kitties_of(owner: &T::AccountId, start: u32, limit: u32) -> Vec<T::KittyIndex> {
+		<UserKitties<T>>::iter_prefix_values(owner)
+			.skip(start as usize)
+			.take(limit as usize)
+			.collect()
+	}
+
+	/// Bytes an owner signs to make `offer`. They name this chain by its genesis hash, so that
+	/// the offer cannot be replayed on another chain.
+	pub fn breeding_offer_payload(offer: &BreedingOfferOf<T>) -> Vec<u8> {
+		let genesis_hash = <frame_system::Module<T>>::block_hash(T::BlockNumber::zero());
+		(BREEDING_OFFER_CONTEXT, genesis_hash, offer).encode()
+	}
+
+	/// DNA of a kitty, if it exists and has hatched.
+	pub fn kitty_dna(kitty_id: T::KittyIndex) -> Option<[u8; 16]> {
+		if <Eggs<T>>::contains_key(kitty_id) {
+			return None;
+		}
+		Self::kitties(kitty_id).map(|kitty| kitty.dna)
+	}
+
+	/// The two kitties `kitty_id` was bred from. Empty for created kitties.
+	pub fn parents_of(kitty_id: T::KittyIndex) -> Vec<T::KittyIndex> {
+		<Parents<T>>::iter_prefix_values(kitty_id).collect()
+	}
+
+	/// Kitties bred from `kitty_id`.
+	pub fn children_of(kitty_id: T::KittyIndex) -> Vec<T::KittyIndex> {
+		<Children<T>>::iter_prefix_values(kitty_id).collect()
 	}
-	fn next_kitty_id() -> sp_std::result::Result<KittyIndex, DispatchError> {
-		let kitty_id = KittiesCount::get();
-		if kitty_id == KittyIndex::max_value() {
-			return Err(Error::<T>::KittiesCountOverflow.into());
+
+	/// Kitties `kitty_id` has been bred with.
+	pub fn breeding_partners_of(kitty_id: T::KittyIndex) -> Vec<T::KittyIndex> {
+		<Breeded<T>>::iter_prefix_values(kitty_id).collect()
+	}
+
+	fn ensure_owner(who: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+		let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+		ensure!(&owner == who, Error::<T>::NotKittyOwner);
+		Ok(())
+	}
+
+	/// Ensure `who` has room for one more kitty.
+	fn ensure_can_own(who: &T::AccountId) -> DispatchResult {
+		ensure!(
+			Self::owned_kitties_count(who) < T::MaxKittiesPerAccount::get(),
+			Error::<T>::TooManyKitties
+		);
+		Ok(())
+	}
+
+	fn ensure_not_in_auction(kitty_id: T::KittyIndex) -> DispatchResult {
+		ensure!(!<Auctions<T>>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+		Ok(())
+	}
+
+	/// Whether `who` may move `kitty_id` out of `owner`'s hands.
+	fn is_approved_or_owner(who: &T::AccountId, owner: &T::AccountId, kitty_id: T::KittyIndex) -> bool {
+		who == owner
+			|| Self::approved(kitty_id).as_ref() == Some(who)
+			|| Self::is_operator(owner, who)
+	}
+
+	/// Hand a kitty and its deposits over to `to`. The receiving account must exist.
+	fn do_transfer(from: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+		Self::ensure_can_own(to)?;
+		let kitty = Self::kitties(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+		T::Currency::repatriate_reserved(from, to, kitty.deposit, BalanceStatus::Reserved)?;
+		if let Some(metadata) = Self::kitty_metadata(kitty_id) {
+			T::Currency::repatriate_reserved(from, to, metadata.deposit, BalanceStatus::Reserved)?;
+		}
+
+		<KittyOwners<T>>::insert(kitty_id, to);
+		<UserKitties<T>>::remove(from, kitty_id);
+		<UserKitties<T>>::insert(to, kitty_id, kitty_id);
+		<OwnedKittiesCount<T>>::mutate(from, |count| *count = count.saturating_sub(1));
+		<OwnedKittiesCount<T>>::mutate(to, |count| *count = count.saturating_add(1));
+		// Neither a listing nor an approval survives a change of owner.
+		<KittyPrices<T>>::remove(kitty_id);
+		<SirePrices<T>>::remove(kitty_id);
+		<Approvals<T>>::remove(kitty_id);
+		Ok(())
+	}
+
+	/// Remove a kitty, hatched or not, and give its deposits back to `owner`.
+	///
+	/// Lineage entries are kept: kitty indices are never reused, so they read as tombstones
+	/// and keep protecting living descendants from inbreeding.
+	fn do_burn(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+		if let Some(egg) = <Eggs<T>>::take(kitty_id) {
+			<EggsOf<T>>::mutate(&egg.laid_by, |count| *count = count.saturating_sub(1));
+		}
+		let deposit = <Kitties<T>>::take(kitty_id).map(|kitty| kitty.deposit).unwrap_or_else(Zero::zero);
+		<KittyOwners<T>>::remove(kitty_id);
+		<UserKitties<T>>::remove(owner, kitty_id);
+		<OwnedKittiesCount<T>>::mutate(owner, |count| *count = count.saturating_sub(1));
+		<KittyPrices<T>>::remove(kitty_id);
+		<SirePrices<T>>::remove(kitty_id);
+		<Approvals<T>>::remove(kitty_id);
+		if let Some(metadata) = <KittyMetadata<T>>::take(kitty_id) {
+			T::Currency::unreserve(owner, metadata.deposit);
+		}
+		T::Currency::unreserve(owner, deposit);
+	}
+
+	/// Settle every auction ending at block `now`.
+	fn settle_auctions(now: T::BlockNumber) {
+		let ending = <AuctionsEnding<T>>::iter_prefix_values(now).collect::<Vec<_>>();
+		<AuctionsEnding<T>>::remove_prefix(now);
+		<AuctionsEndingCount<T>>::remove(now);
+		for kitty_id in ending {
+			if let Some(auction) = <Auctions<T>>::take(kitty_id) {
+				Self::settle_auction(kitty_id, auction);
+			}
+		}
+	}
+
+	/// Pay the seller out of the winning bid and hand the kitty over to the winner.
+	///
+	/// If the winner has run out of room for kitties since bidding, or the seller cannot be paid
+	/// the full price, the kitty stays with the seller and the bid is given back, as if there had
+	/// been no bid.
+	fn settle_auction(kitty_id: T::KittyIndex, auction: AuctionOf<T>) {
+		match auction.best_bid {
+			Some((winner, price)) if Self::sell_at_auction(&auction.seller, &winner, kitty_id, price).is_ok() => {
+				Self::deposit_event(RawEvent::AuctionWon(auction.seller, winner, kitty_id, price));
+			}
+			Some((winner, price)) => {
+				T::Currency::unreserve(&winner, price);
+				Self::deposit_event(RawEvent::AuctionUnsold(auction.seller, kitty_id));
+			}
+			None => Self::deposit_event(RawEvent::AuctionUnsold(auction.seller, kitty_id)),
+		}
+	}
+
+	/// Hand a kitty over to the winner of its auction, paying its `price` to the seller out of
+	/// the reserved bid. Nothing changes unless both succeed in full.
+	#[transactional]
+	fn sell_at_auction(
+		seller: &T::AccountId,
+		winner: &T::AccountId,
+		kitty_id: T::KittyIndex,
+		price: BalanceOf<T>,
+	) -> DispatchResult {
+		Self::do_transfer(seller, winner, kitty_id)?;
+		let unpaid = T::Currency::repatriate_reserved(winner, seller, price, BalanceStatus::Free)?;
+		ensure!(unpaid.is_zero(), Error::<T>::BidNotReserved);
+		Ok(())
+	}
+
+	/// Stop the auction of a kitty, if any, and give the highest bid back.
+	fn cancel_auction(kitty_id: T::KittyIndex) {
+		if let Some(auction) = <Auctions<T>>::take(kitty_id) {
+			<AuctionsEnding<T>>::remove(auction.end, kitty_id);
+			<AuctionsEndingCount<T>>::mutate(auction.end, |count| *count = count.saturating_sub(1));
+			if let Some((bidder, amount)) = auction.best_bid {
+				T::Currency::unreserve(&bidder, amount);
+			}
 		}
+	}
+
+	/// Create a first generation kitty for `owner`. It hatches later.
+	fn do_create(owner: &T::AccountId) -> sp_std::result::Result<T::KittyIndex, DispatchError> {
+		Self::ensure_can_own(owner)?;
+		let kitty_id = Self::next_kitty_id()?;
+		Self::ensure_can_lay(owner)?;
+		let deposit = T::KittyReserve::get();
+		T::Currency::reserve(owner, deposit)?;
+		Self::lay_egg(owner, kitty_id, None);
+		let kitty = Kitty { dna: genome::new_dna([0; 16]), generation: 0, next_breed_block: Zero::zero(), deposit };
+		Self::insert_kitty(owner, kitty_id, kitty);
 		Ok(kitty_id)
 	}
 
-	fn random_value(sender: &T::AccountId) -> [u8;16] {
-		let payload = (T::Randomness::random_seed(),
-					   &sender, <frame_system::Module<T>>::extrinsic_index());
-		payload.using_encoded(blake2_128)
+	/// Ensure `who` may lay one more egg.
+	fn ensure_can_lay(who: &T::AccountId) -> DispatchResult {
+		ensure!(Self::eggs_of(who) < T::MaxEggsPerAccount::get(), Error::<T>::TooManyEggs);
+		Ok(())
 	}
 
-	fn combine_dna(dna1: u8, dna2: u8, selector: u8) -> u8 {
-		(selector & dna1) | (!selector & dna2)
+	/// Queue `kitty_id`, the newest kitty, to hatch once `T::Randomness` is made of later
+	/// commitments. Callers check that `laid_by` may lay it.
+	fn lay_egg(laid_by: &T::AccountId, kitty_id: T::KittyIndex, parents: Option<([u8; 16], [u8; 16])>) {
+		let laid_at = <frame_system::Module<T>>::block_number();
+		<EggsOf<T>>::mutate(laid_by, |count| *count = count.saturating_add(1));
+		<Eggs<T>>::insert(kitty_id, Egg { laid_by: laid_by.clone(), laid_at, parents });
 	}
 
-	fn do_breed(sender: &T::AccountId, kitty_id_1: KittyIndex, kitty_id_2: KittyIndex) -> sp_std::result::Result<KittyIndex, DispatchError>{
-		let kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
-		let kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
+	/// Hatch the eggs laid before block `since` from `random`, oldest first, looking at
+	/// `MaxHatchPerBlock` kitties at most. Returns how many kitties were looked at.
+	fn hatch_eggs(random: T::Hash, since: T::BlockNumber) -> u32 {
+		let count = Self::kitties_count();
+		let mut kitty_id = Self::next_to_hatch();
+		let mut looked_at = 0;
+		while kitty_id < count && looked_at < T::MaxHatchPerBlock::get() {
+			match Self::egg(kitty_id) {
+				Some(egg) if egg.laid_at >= since => break,
+				Some(egg) => Self::hatch(kitty_id, egg, &random),
+				// Burnt before hatching.
+				None => {}
+			}
+			kitty_id += One::one();
+			looked_at += 1;
+		}
+		<NextToHatch<T>>::put(kitty_id);
+		looked_at
+	}
+
+	/// Draw the DNA of an egg from `random`.
+	fn hatch(kitty_id: T::KittyIndex, egg: EggOf<T>, random: &T::Hash) {
+		<Eggs<T>>::remove(kitty_id);
+		<EggsOf<T>>::mutate(&egg.laid_by, |count| *count = count.saturating_sub(1));
+		let mut kitty = match Self::kitties(kitty_id) {
+			Some(kitty) => kitty,
+			None => return,
+		};
+		let selector = (random, kitty_id).using_encoded(blake2_128);
+		kitty.dna = match egg.parents {
+			Some((dna1, dna2)) => genome::breed(&dna1, &dna2, &selector),
+			None => genome::new_dna(selector),
+		};
+		genome::set_generation(&mut kitty.dna, kitty.generation);
+		Self::deposit_event(RawEvent::KittyHatched(kitty_id, kitty.dna));
+		<Kitties<T>>::insert(kitty_id, kitty);
+	}
+
+	fn next_kitty_id() -> sp_std::result::Result<T::KittyIndex, DispatchError> {
+		let kitty_id = Self::kitties_count();
+		// The count has to stay representable once this kitty is added.
+		kitty_id.checked_add(&One::one()).ok_or(Error::<T>::KittiesCountOverflow)?;
+		Ok(kitty_id)
+	}
+
+	/// Blocks a kitty of `generation` has to rest after breeding.
+	fn breed_cooldown(generation: u16) -> T::BlockNumber {
+		let multiplier = u32::from(genome::cooldown_index(generation)) + 1;
+		T::BreedCooldown::get().saturating_mul(multiplier.into())
+	}
+
+	/// Ancestors of `kitty_id` up to `depth` generations back, parents being the first.
+	fn ancestors(kitty_id: T::KittyIndex, depth: u32) -> BTreeSet<T::KittyIndex> {
+		let mut ancestors = BTreeSet::new();
+		let mut generation = vec![kitty_id];
+		for _ in 0..depth {
+			let mut parents = Vec::new();
+			for id in generation {
+				for parent in <Parents<T>>::iter_prefix_values(id) {
+					if ancestors.insert(parent) {
+						parents.push(parent);
+					}
+				}
+			}
+			if parents.is_empty() {
+				break;
+			}
+			generation = parents;
+		}
+		ancestors
+	}
+
+	/// Whether one kitty descends from the other or both share an ancestor within
+	/// `MaxInbreedingDepth` generations.
+	fn are_related(kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> bool {
+		let depth = T::MaxInbreedingDepth::get().max(1);
+		let ancestors_1 = Self::ancestors(kitty_id_1, depth);
+		let ancestors_2 = Self::ancestors(kitty_id_2, depth);
+
+		ancestors_1.contains(&kitty_id_2)
+			|| ancestors_2.contains(&kitty_id_1)
+			|| !ancestors_1.is_disjoint(&ancestors_2)
+	}
+
+	/// Breed two hatched kitties into a new one owned by `sender`, who pays its deposit. The
+	/// child hatches later. Callers check that `sender` may use both parents.
+	fn do_breed(sender: &T::AccountId, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> sp_std::result::Result<T::KittyIndex, DispatchError>{
+		let mut kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
+		let mut kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
 
 		ensure!(kitty_id_1 != kitty_id_2, Error::<T>::RequireDifferentParent);
+		Self::ensure_not_in_auction(kitty_id_1)?;
+		Self::ensure_not_in_auction(kitty_id_2)?;
+		ensure!(!Self::are_related(kitty_id_1, kitty_id_2), Error::<T>::RelatedKitties);
+		ensure!(
+			!<Eggs<T>>::contains_key(kitty_id_1) && !<Eggs<T>>::contains_key(kitty_id_2),
+			Error::<T>::KittyNotHatched
+		);
+
+		let now = <frame_system::Module<T>>::block_number();
+		ensure!(
+			kitty1.next_breed_block <= now && kitty2.next_breed_block <= now,
+			Error::<T>::KittyOnCooldown
+		);
 
+		Self::ensure_can_own(sender)?;
 		let kitty_id = Self::next_kitty_id()?;
+		Self::ensure_can_lay(sender)?;
+		let deposit = T::KittyReserve::get();
+		T::Currency::reserve(sender, deposit)?;
+		Self::lay_egg(sender, kitty_id, Some((kitty1.dna, kitty2.dna)));
 
-		let kitty1_dna = kitty1.0;
-		let kitty2_dna = kitty2.0;
-		let selector = Self::random_value(&sender);
-		let mut new_dna = [0u8; 16];
+		let generation = kitty1.generation.max(kitty2.generation).saturating_add(1);
+		let mut dna = [0; 16];
+		genome::set_generation(&mut dna, generation);
+		Self::insert_kitty(sender, kitty_id, Kitty {
+			dna,
+			generation,
+			next_breed_block: now.saturating_add(Self::breed_cooldown(generation)),
+			deposit,
+		});
 
-		for i in 0..kitty1_dna.len() {
-			new_dna[i] = Self::combine_dna(kitty1_dna[i], kitty2_dna[i], selector[i]);
-		}
-		Self::insert_kitty(sender, kitty_id, Kitty(new_dna));
+		kitty1.next_breed_block = now.saturating_add(Self::breed_cooldown(kitty1.generation));
+		kitty2.next_breed_block = now.saturating_add(Self::breed_cooldown(kitty2.generation));
+		<Kitties<T>>::insert(kitty_id_1, kitty1);
+		<Kitties<T>>::insert(kitty_id_2, kitty2);
 
 		//处理关于breed的关系
-		Parents::insert(kitty_id, kitty_id_1, kitty_id_1);
-		Parents::insert(kitty_id, kitty_id_2, kitty_id_2);
-		Children::insert(kitty_id_1, kitty_id, kitty_id);
-		Children::insert(kitty_id_2, kitty_id, kitty_id);
-		Breeded::insert(kitty_id_1, kitty_id_2, kitty_id_2);
-		Breeded::insert(kitty_id_2, kitty_id_1, kitty_id_1);
+		<Parents<T>>::insert(kitty_id, kitty_id_1, kitty_id_1);
+		<Parents<T>>::insert(kitty_id, kitty_id_2, kitty_id_2);
+		<Children<T>>::insert(kitty_id_1, kitty_id, kitty_id);
+		<Children<T>>::insert(kitty_id_2, kitty_id, kitty_id);
+		<Breeded<T>>::insert(kitty_id_1, kitty_id_2, kitty_id_2);
+		<Breeded<T>>::insert(kitty_id_2, kitty_id_1, kitty_id_1);
 
 		Ok(kitty_id)
 	}
@@ -122,33 +712,352 @@ decl_module! {
 		type Error = Error<T>;
 		fn deposit_event() = default;
 
-		#[weight = 0]
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get() == Releases::V1_0_0 {
+				let weight = migration::migrate_to_v2::<T>();
+				StorageVersion::put(Releases::V2_0_0);
+				weight
+			} else {
+				0
+			}
+		}
+
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let (random, since) = T::Randomness::fresh_random(HATCH_SUBJECT);
+			let hatched = Self::hatch_eggs(random, since);
+			let ending = Self::auctions_ending_count(now);
+			T::DbWeight::get().reads(3)
+				.saturating_add(T::WeightInfo::hatch_eggs(hatched))
+				.saturating_add(T::WeightInfo::settle_auctions(ending))
+		}
+
+		fn on_finalize(now: T::BlockNumber) {
+			Self::settle_auctions(now);
+		}
+
+		/// Create a first generation kitty. It hatches once a round of commitments made after
+		/// this block has been revealed.
+		#[weight = T::WeightInfo::create()]
 		pub fn create(origin) {
 			let sender = ensure_signed(origin)?;
-			let kitty_id = Self::next_kitty_id()?;
-			let dna = Self::random_value(&sender);
-			let kitty = Kitty(dna);
-			Self::insert_kitty(&sender, kitty_id, kitty);
+			let kitty_id = Self::do_create(&sender)?;
 			Self::deposit_event(RawEvent::KittyCreated(sender, kitty_id));
 		}
 
-		#[weight = 0]
-		pub fn transfer(origin, to: T::AccountId, kitty_id: KittyIndex) {
+		/// Create `count` kitties at once. Either all of them are created or none is.
+		#[weight = T::WeightInfo::create_batch(*count)]
+		#[transactional]
+		pub fn create_batch(origin, count: u32) {
 			let sender = ensure_signed(origin)?;
-			Self::kitties(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
-			<KittyOwners<T>>::insert(kitty_id, to.clone());
+			ensure!(count <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
+
+			for _ in 0..count {
+				let kitty_id = Self::do_create(&sender)?;
+				Self::deposit_event(RawEvent::KittyCreated(sender.clone(), kitty_id));
+			}
+		}
 
-			<UserKitties<T>>::insert(&to, kitty_id, kitty_id);
-			<UserKitties<T>>::remove(&sender, kitty_id);
+		#[weight = T::WeightInfo::transfer()]
+		pub fn transfer(origin, to: T::AccountId, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			ensure!(sender != to, Error::<T>::TransferToSelf);
+			Self::ensure_owner(&sender, kitty_id)?;
+			Self::ensure_not_in_auction(kitty_id)?;
+			Self::do_transfer(&sender, &to, kitty_id)?;
 			Self::deposit_event(RawEvent::Transferred(sender, to, kitty_id));
 		}
 
-		#[weight = 0]
-		pub fn breed(origin, kitty_id_1: KittyIndex, kitty_id_2: KittyIndex) {
+		/// Transfer several kitties, each to its own recipient. Either all of them are
+		/// transferred or none is.
+		#[weight = T::WeightInfo::transfer_batch(transfers.len() as u32)]
+		#[transactional]
+		pub fn transfer_batch(origin, transfers: Vec<(T::AccountId, T::KittyIndex)>) {
+			let sender = ensure_signed(origin)?;
+			ensure!(transfers.len() <= T::MaxBatchSize::get() as usize, Error::<T>::BatchTooLarge);
+
+			for (to, kitty_id) in transfers {
+				ensure!(sender != to, Error::<T>::TransferToSelf);
+				Self::ensure_owner(&sender, kitty_id)?;
+				Self::ensure_not_in_auction(kitty_id)?;
+				Self::do_transfer(&sender, &to, kitty_id)?;
+				Self::deposit_event(RawEvent::Transferred(sender.clone(), to, kitty_id));
+			}
+		}
+
+		#[weight = T::WeightInfo::breed(T::MaxInbreedingDepth::get().max(1))]
+		pub fn breed(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) {
 			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&sender, kitty_id_1)?;
+			Self::ensure_owner(&sender, kitty_id_2)?;
 			let new_kitty_id = Self::do_breed(&sender, kitty_id_1, kitty_id_2)?;
 			Self::deposit_event(RawEvent::KittyCreated(sender, new_kitty_id));
 		}
+
+		/// Offer a kitty as a sire for `price`, or withdraw the offer by passing `None`.
+		/// The offer is withdrawn when the kitty changes hands.
+		#[weight = T::WeightInfo::set_sire_price()]
+		pub fn set_sire_price(origin, kitty_id: T::KittyIndex, price: Option<BalanceOf<T>>) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&sender, kitty_id)?;
+			Self::ensure_not_in_auction(kitty_id)?;
+
+			<SirePrices<T>>::mutate_exists(kitty_id, |p| *p = price);
+			Self::deposit_event(RawEvent::SirePriceSet(sender, kitty_id, price));
+		}
+
+		/// Breed one of your kitties with a sire offered by another owner, paying its fee as
+		/// long as it is not above `max_price`. The sire stays with its owner and the child
+		/// goes to the caller.
+		#[weight = T::WeightInfo::breed_with_sire(T::MaxInbreedingDepth::get().max(1))]
+		#[transactional]
+		pub fn breed_with_sire(
+			origin,
+			kitty_id: T::KittyIndex,
+			sire_id: T::KittyIndex,
+			max_price: BalanceOf<T>,
+		) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&sender, kitty_id)?;
+			let sire_owner = Self::kitty_owner(sire_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(sire_owner != sender, Error::<T>::RentFromSelf);
+
+			let price = Self::sire_price(sire_id).ok_or(Error::<T>::SireNotForRent)?;
+			ensure!(max_price >= price, Error::<T>::PriceTooLow);
+
+			T::Currency::transfer(&sender, &sire_owner, price, ExistenceRequirement::KeepAlive)?;
+			let new_kitty_id = Self::do_breed(&sender, kitty_id, sire_id)?;
+
+			Self::deposit_event(RawEvent::KittyCreated(sender.clone(), new_kitty_id));
+			Self::deposit_event(RawEvent::SireRented(sender, sire_owner, sire_id, new_kitty_id, price));
+		}
+
+		/// List a kitty for sale, or remove the listing by passing `None`.
+		#[weight = T::WeightInfo::ask()]
+		pub fn ask(origin, kitty_id: T::KittyIndex, price: Option<BalanceOf<T>>) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&sender, kitty_id)?;
+			Self::ensure_not_in_auction(kitty_id)?;
+
+			<KittyPrices<T>>::mutate_exists(kitty_id, |p| *p = price);
+			Self::deposit_event(RawEvent::PriceSet(sender, kitty_id, price));
+		}
+
+		/// Buy a listed kitty, paying its asking price as long as it is not above `max_price`.
+		#[weight = T::WeightInfo::buy()]
+		#[transactional]
+		pub fn buy(origin, kitty_id: T::KittyIndex, max_price: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(owner != sender, Error::<T>::BuyFromSelf);
+			Self::ensure_not_in_auction(kitty_id)?;
+
+			let price = Self::kitty_price(kitty_id).ok_or(Error::<T>::KittyNotForSale)?;
+			ensure!(max_price >= price, Error::<T>::PriceTooLow);
+
+			T::Currency::transfer(&sender, &owner, price, ExistenceRequirement::KeepAlive)?;
+			Self::do_transfer(&owner, &sender, kitty_id)?;
+
+			Self::deposit_event(RawEvent::Sold(owner, sender, kitty_id, price));
+		}
+
+		/// Allow `spender` to transfer a kitty, or clear the approval with `None`. Callable by
+		/// the owner or one of its operators. The approval is cleared when the kitty moves.
+		#[weight = T::WeightInfo::approve()]
+		pub fn approve(origin, kitty_id: T::KittyIndex, spender: Option<T::AccountId>) {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(sender == owner || Self::is_operator(&owner, &sender), Error::<T>::NotKittyOwner);
+			ensure!(spender.as_ref() != Some(&owner), Error::<T>::ApproveToSelf);
+
+			<Approvals<T>>::mutate_exists(kitty_id, |a| *a = spender.clone());
+			Self::deposit_event(RawEvent::Approval(owner, spender, kitty_id));
+		}
+
+		/// Grant or revoke `operator` the right to transfer all of your kitties.
+		#[weight = T::WeightInfo::set_approval_for_all()]
+		pub fn set_approval_for_all(origin, operator: T::AccountId, approved: bool) {
+			let sender = ensure_signed(origin)?;
+			ensure!(sender != operator, Error::<T>::ApproveToSelf);
+
+			if approved {
+				<Operators<T>>::insert(&sender, &operator, true);
+			} else {
+				<Operators<T>>::remove(&sender, &operator);
+			}
+			Self::deposit_event(RawEvent::ApprovalForAll(sender, operator, approved));
+		}
+
+		/// Transfer a kitty owned by `from`, as its owner, approved spender or operator.
+		#[weight = T::WeightInfo::transfer_from()]
+		pub fn transfer_from(origin, from: T::AccountId, to: T::AccountId, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			ensure!(from != to, Error::<T>::TransferToSelf);
+			Self::ensure_owner(&from, kitty_id)?;
+			Self::ensure_not_in_auction(kitty_id)?;
+			ensure!(Self::is_approved_or_owner(&sender, &from, kitty_id), Error::<T>::NotApproved);
+
+			Self::do_transfer(&from, &to, kitty_id)?;
+			Self::deposit_event(RawEvent::Transferred(from, to, kitty_id));
+		}
+
+		/// Destroy one of your kitties and get its deposit back. Kitties cannot be burnt before
+		/// they hatch, so that owners cannot throw away the ones they do not like as soon as the
+		/// randomness they hatch from is known.
+		#[weight = T::WeightInfo::burn()]
+		pub fn burn(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&sender, kitty_id)?;
+			Self::ensure_not_in_auction(kitty_id)?;
+			ensure!(!<Eggs<T>>::contains_key(kitty_id), Error::<T>::KittyNotHatched);
+			Self::do_burn(&sender, kitty_id);
+			Self::deposit_event(RawEvent::KittyBurned(sender, kitty_id));
+		}
+
+		/// Destroy any kitty. The deposit goes back to its owner and a running auction is
+		/// cancelled, giving the highest bid back.
+		#[weight = T::WeightInfo::force_burn()]
+		pub fn force_burn(origin, kitty_id: T::KittyIndex) {
+			ensure_root(origin)?;
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			Self::cancel_auction(kitty_id);
+			Self::do_burn(&owner, kitty_id);
+			Self::deposit_event(RawEvent::KittyBurned(owner, kitty_id));
+		}
+
+		/// Breed one of your kitties with the kitty of a signed `BreedingOffer`, paying its price
+		/// to the owner who signed it. The child goes to the caller.
+		#[weight = T::WeightInfo::accept_breeding_offer(T::MaxInbreedingDepth::get().max(1))]
+		#[transactional]
+		pub fn accept_breeding_offer(
+			origin,
+			kitty_id: T::KittyIndex,
+			offer: BreedingOfferOf<T>,
+			signature: T::OffchainSignature,
+		) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&sender, kitty_id)?;
+			ensure!(offer.owner != sender, Error::<T>::RentFromSelf);
+			ensure!(
+				<frame_system::Module<T>>::block_number() <= offer.expiry,
+				Error::<T>::OfferExpired
+			);
+			ensure!(!Self::offer_nonce_used(&offer.owner, offer.nonce), Error::<T>::InvalidOfferNonce);
+			ensure!(
+				signature.verify(&Self::breeding_offer_payload(&offer)[..], &offer.owner),
+				Error::<T>::InvalidSignature
+			);
+			Self::ensure_owner(&offer.owner, offer.kitty_id)?;
+
+			<UsedOfferNonces<T>>::insert(&offer.owner, offer.nonce, true);
+			T::Currency::transfer(&sender, &offer.owner, offer.price, ExistenceRequirement::KeepAlive)?;
+			let new_kitty_id = Self::do_breed(&sender, kitty_id, offer.kitty_id)?;
+
+			Self::deposit_event(RawEvent::KittyCreated(sender.clone(), new_kitty_id));
+			Self::deposit_event(RawEvent::SireRented(sender, offer.owner, offer.kitty_id, new_kitty_id, offer.price));
+		}
+
+		/// Invalidate your breeding offer carrying `nonce`, if it has not been accepted yet.
+		#[weight = T::WeightInfo::cancel_breeding_offer()]
+		pub fn cancel_breeding_offer(origin, nonce: u32) {
+			let sender = ensure_signed(origin)?;
+			ensure!(!Self::offer_nonce_used(&sender, nonce), Error::<T>::InvalidOfferNonce);
+			<UsedOfferNonces<T>>::insert(&sender, nonce, true);
+			Self::deposit_event(RawEvent::BreedingOfferCancelled(sender, nonce));
+		}
+
+		/// Name a kitty and point to its image. A deposit is reserved for every byte of `name`
+		/// and `uri`, replacing the one of the previous metadata.
+		#[weight = T::WeightInfo::set_metadata(name.len() as u32, uri.len() as u32)]
+		pub fn set_metadata(origin, kitty_id: T::KittyIndex, name: Vec<u8>, uri: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&sender, kitty_id)?;
+			Self::ensure_not_in_auction(kitty_id)?;
+			ensure!(name.len() <= T::MaxNameLength::get() as usize, Error::<T>::NameTooLong);
+			ensure!(uri.len() <= T::MaxUriLength::get() as usize, Error::<T>::UriTooLong);
+
+			let bytes = (name.len() + uri.len()) as u32;
+			let deposit = T::MetadataDepositPerByte::get().saturating_mul(bytes.into());
+			let old_deposit = Self::kitty_metadata(kitty_id).map(|m| m.deposit).unwrap_or_else(Zero::zero);
+			if deposit > old_deposit {
+				T::Currency::reserve(&sender, deposit - old_deposit)?;
+			} else {
+				T::Currency::unreserve(&sender, old_deposit - deposit);
+			}
+
+			<KittyMetadata<T>>::insert(kitty_id, Metadata { name, uri, deposit });
+			Self::deposit_event(RawEvent::MetadataSet(sender, kitty_id));
+		}
+
+		/// Remove the name and image URI of a kitty and get their deposit back.
+		#[weight = T::WeightInfo::clear_metadata()]
+		pub fn clear_metadata(origin, kitty_id: T::KittyIndex) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&sender, kitty_id)?;
+			Self::ensure_not_in_auction(kitty_id)?;
+			let metadata = <KittyMetadata<T>>::take(kitty_id).ok_or(Error::<T>::NoMetadata)?;
+			T::Currency::unreserve(&sender, metadata.deposit);
+			Self::deposit_event(RawEvent::MetadataCleared(sender, kitty_id));
+		}
+
+		/// Put a kitty up for auction for `duration` blocks. It is locked until the auction is
+		/// settled at the end of its last block, and any sale or sire offer is withdrawn.
+		/// At most `MaxAuctionsEndingPerBlock` auctions may end in the same block.
+		#[weight = T::WeightInfo::start_auction()]
+		pub fn start_auction(
+			origin,
+			kitty_id: T::KittyIndex,
+			reserve: BalanceOf<T>,
+			duration: T::BlockNumber,
+		) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&sender, kitty_id)?;
+			Self::ensure_not_in_auction(kitty_id)?;
+			ensure!(
+				!duration.is_zero() && duration <= T::MaxAuctionDuration::get(),
+				Error::<T>::InvalidAuctionDuration
+			);
+
+			let end = <frame_system::Module<T>>::block_number().saturating_add(duration);
+			ensure!(
+				Self::auctions_ending_count(end) < T::MaxAuctionsEndingPerBlock::get(),
+				Error::<T>::TooManyAuctionsEnding
+			);
+
+			<KittyPrices<T>>::remove(kitty_id);
+			<SirePrices<T>>::remove(kitty_id);
+			<Auctions<T>>::insert(kitty_id, Auction {
+				seller: sender.clone(),
+				reserve,
+				end,
+				best_bid: None,
+			});
+			<AuctionsEnding<T>>::insert(end, kitty_id, kitty_id);
+			<AuctionsEndingCount<T>>::mutate(end, |count| *count = count.saturating_add(1));
+
+			Self::deposit_event(RawEvent::AuctionStarted(sender, kitty_id, reserve, end));
+		}
+
+		/// Bid on a running auction. The amount is reserved until the bid is outbid or wins.
+		#[weight = T::WeightInfo::bid()]
+		pub fn bid(origin, kitty_id: T::KittyIndex, amount: BalanceOf<T>) {
+			let sender = ensure_signed(origin)?;
+			let mut auction = Self::auction(kitty_id).ok_or(Error::<T>::NotInAuction)?;
+			ensure!(auction.seller != sender, Error::<T>::BidOnOwnAuction);
+			ensure!(amount >= auction.reserve, Error::<T>::BidTooLow);
+			if let Some((_, best)) = &auction.best_bid {
+				ensure!(amount > *best, Error::<T>::BidTooLow);
+			}
+			Self::ensure_can_own(&sender)?;
+
+			T::Currency::reserve(&sender, amount)?;
+			if let Some((bidder, best)) = auction.best_bid.take() {
+				T::Currency::unreserve(&bidder, best);
+			}
+			auction.best_bid = Some((sender.clone(), amount));
+			<Auctions<T>>::insert(kitty_id, auction);
+
+			Self::deposit_event(RawEvent::BidPlaced(sender, kitty_id, amount));
+		}
 	}
 }
 
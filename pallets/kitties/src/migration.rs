@@ -0,0 +1,77 @@
+//! Storage migrations for pallet_kitties.
+
+use super::*;
+use frame_support::{
+	Blake2_128Concat, StorageHasher, StoragePrefixedMap,
+	storage::migration::get_storage_value,
+};
+
+/// Most kitties `migrate_to_v2` converts. It does so in a single block, which takes about a
+/// dozen storage accesses per kitty; chains holding more than this must not run it.
+pub const MAX_MIGRATED_KITTIES: u32 = 1_000;
+
+/// A kitty as stored before `StorageVersion` was introduced: its DNA and nothing else.
+#[derive(Encode, Decode)]
+pub struct OldKitty(pub [u8; 16]);
+
+/// Convert every `OldKitty` into a `Kitty` record.
+///
+/// Kitty indices were plain `u32`s in the old layout and `T::KittyIndex` is expected to keep
+/// their encoding, so that every other map stays readable as it is.
+///
+/// Kitties are visited in index order, so the parents of a bred kitty are already converted
+/// when its generation is derived from theirs. Ownership lists and counters are rebuilt from
+/// `KittyOwners`, which the old `transfer` could leave out of step with `UserKitties`. Owners
+/// are asked for the `KittyReserve` of each kitty; kitties of owners who cannot cover it are
+/// kept with a zero `deposit`, so that burning or moving them never touches funds reserved for
+/// anything else.
+///
+/// Panics if there are more than `MAX_MIGRATED_KITTIES` kitties. Returns the weight of the
+/// storage reads and writes made.
+pub fn migrate_to_v2<T: Trait>() -> Weight {
+	let count: u32 = get_storage_value::<u32>(b"Kitties", b"KittiesCount", &[]).unwrap_or(0);
+	assert!(count <= MAX_MIGRATED_KITTIES, "too many kitties to migrate in one block");
+	let (mut reads, mut writes): (Weight, Weight) = (1, 2);
+
+	<UserKitties<T>>::remove_all();
+	<OwnedKittiesCount<T>>::remove_all();
+
+	for index in 0..count {
+		let key = Blake2_128Concat::hash(&index.encode());
+		reads += 1;
+		let old = match get_storage_value::<OldKitty>(b"Kitties", b"Kitties", &key) {
+			Some(old) => old,
+			None => continue,
+		};
+		let kitty_id = T::KittyIndex::from(index);
+
+		let parents = <Module<T>>::parents_of(kitty_id);
+		reads += 1 + parents.len() as Weight;
+		let generation = parents.into_iter()
+			.filter_map(|parent| <Module<T>>::kitties(parent))
+			.map(|parent| parent.generation.saturating_add(1))
+			.max()
+			.unwrap_or(0);
+		let mut dna = old.0;
+		genome::set_generation(&mut dna, generation);
+
+		let mut deposit = Zero::zero();
+		reads += 1;
+		if let Some(owner) = <Module<T>>::kitty_owner(kitty_id) {
+			reads += 2;
+			writes += 3;
+			<UserKitties<T>>::insert(&owner, kitty_id, kitty_id);
+			<OwnedKittiesCount<T>>::mutate(&owner, |count| *count = count.saturating_add(1));
+			if T::Currency::reserve(&owner, T::KittyReserve::get()).is_ok() {
+				deposit = T::KittyReserve::get();
+			}
+		}
+		<Kitties<T>>::insert(kitty_id, Kitty { dna, generation, next_breed_block: Zero::zero(), deposit });
+		writes += 1;
+	}
+	// Old kitties had their DNA from the start.
+	<NextToHatch<T>>::put(T::KittyIndex::from(count));
+	writes += 1;
+
+	T::DbWeight::get().reads_writes(reads, writes)
+}
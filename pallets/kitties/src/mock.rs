@@ -2,10 +2,10 @@ use crate::{Module, Trait};
 use sp_core::H256;
 use frame_support::{impl_outer_origin, parameter_types, weights::Weight, impl_outer_event};
 use sp_runtime::{
-	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
+	traits::{BlakeTwo256, IdentityLookup}, testing::{Header, TestSignature, UintAuthorityId}, Perbill,
 };
 use frame_system as system;
-use pallet_randomness_collective_flip;
+use pallet_balances;
 
 impl_outer_origin! {
 	pub enum Origin for Test {}
@@ -18,6 +18,8 @@ mod kitties_event {
 impl_outer_event! {
 	pub enum TestEvent for Test{
 		system<T>,
+		pallet_balances<T>,
+		pallet_commit_reveal<T>,
 		kitties_event<T>,
 	}
 }
@@ -55,21 +57,108 @@ impl system::Trait for Test {
 	type AvailableBlockRatio = AvailableBlockRatio;
 	type Version = ();
 	type PalletInfo = ();
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
 }
 
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+	pub const KittyReserve: u64 = 100;
+	pub const BreedCooldown: u64 = 5;
+	pub const MaxInbreedingDepth: u32 = 2;
+	pub const MaxAuctionDuration: u64 = 20;
+	pub const MaxAuctionsEndingPerBlock: u32 = 3;
+	pub const MaxKittiesPerAccount: u32 = 20;
+	pub const MaxBatchSize: u32 = 5;
+	pub const MaxEggsPerAccount: u32 = 10;
+	pub const MaxHatchPerBlock: u32 = 15;
+	pub const MaxNameLength: u32 = 8;
+	pub const MaxUriLength: u32 = 16;
+	pub const MetadataDepositPerByte: u64 = 2;
+}
+
+impl pallet_balances::Trait for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = system::Module<Test>;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const CommitDeposit: u64 = 100;
+	// Long enough for no round to close in the first blocks most tests run in.
+	pub const RoundLength: u64 = 50;
+}
+
+impl pallet_commit_reveal::Trait for Test {
+	type Event = TestEvent;
+	type Currency = pallet_balances::Module<Test>;
+	type CommitDeposit = CommitDeposit;
+	type RoundLength = RoundLength;
+}
+
+pub type KittyIndex = u32;
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct TestOfferSigner;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::OfferSigner<u64, TestSignature> for TestOfferSigner {
+	fn signer() -> u64 {
+		1_000
+	}
+
+	fn sign(signer: &u64, payload: &[u8]) -> TestSignature {
+		TestSignature(*signer, payload.to_vec())
+	}
+}
+
 impl Trait for Test {
 	type Event = TestEvent;
-	type KittyIndex = u32;
-	type Randomness = pallet_randomness_collective_flip::Module<Test>;
+	type KittyIndex = KittyIndex;
+	type Randomness = CommitReveal;
+	type Currency = pallet_balances::Module<Test>;
+	type KittyReserve = KittyReserve;
+	type BreedCooldown = BreedCooldown;
+	type MaxInbreedingDepth = MaxInbreedingDepth;
+	type MaxAuctionDuration = MaxAuctionDuration;
+	type MaxAuctionsEndingPerBlock = MaxAuctionsEndingPerBlock;
+	type MaxKittiesPerAccount = MaxKittiesPerAccount;
+	type MaxNameLength = MaxNameLength;
+	type MaxUriLength = MaxUriLength;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type MaxBatchSize = MaxBatchSize;
+	type MaxEggsPerAccount = MaxEggsPerAccount;
+	type MaxHatchPerBlock = MaxHatchPerBlock;
+	type OffchainSignature = TestSignature;
+	type OffchainPublic = UintAuthorityId;
+	#[cfg(feature = "runtime-benchmarks")]
+	type OfferSigner = TestOfferSigner;
+	type WeightInfo = ();
 }
 
 pub type KittiesModule = Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type CommitReveal = pallet_commit_reveal::Module<Test>;
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	new_test_ext_with_kitties(vec![])
+}
+
+// Build genesis storage with some kitties minted at genesis.
+pub fn new_test_ext_with_kitties(kitties: Vec<(u64, Option<[u8; 16]>)>) -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 10_000), (2, 10_000), (3, 10)],
+	}.assimilate_storage(&mut t).unwrap();
+	crate::GenesisConfig::<Test> {
+		kitties,
+	}.assimilate_storage(&mut t).unwrap();
+	t.into()
 }
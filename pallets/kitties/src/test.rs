@@ -1,11 +1,14 @@
 use crate::{Error, mock::*};
 use frame_support::{
 	assert_ok, assert_noop,
+	dispatch::DispatchError,
 	traits::{OnFinalize, OnInitialize},
 };
 use super::*;
 use frame_system::Phase;
 use frame_system::EventRecord;
+use sp_core::H256;
+use sp_runtime::{testing::TestSignature, traits::{BlakeTwo256, Hash}};
 
 
 pub type System = frame_system::Module<Test>;
@@ -13,9 +16,25 @@ pub type System = frame_system::Module<Test>;
 fn run_to_block(n: u64) {
 	while System::block_number() < n {
 		KittiesModule::on_finalize(System::block_number());
+		CommitReveal::on_finalize(System::block_number());
 		System::on_finalize(System::block_number());
 		System::set_block_number(System::block_number() + 1);
 		System::on_initialize(System::block_number());
+		CommitReveal::on_initialize(System::block_number());
+		KittiesModule::on_initialize(System::block_number());
+	}
+}
+
+/// Make `seed` the randomness of a closed round of commitments made from block `since` on.
+fn close_round(seed: H256, since: u64) {
+	pallet_commit_reveal::Seed::<Test>::put(seed);
+	pallet_commit_reveal::SeedSince::<Test>::put(since);
+}
+
+/// Close a round of commitments made after this block, hatching every egg laid so far.
+fn hatch_eggs() {
+	close_round(BlakeTwo256::hash_of(&CommitReveal::seed()), System::block_number() + 1);
+	while KittiesModule::next_to_hatch() < KittiesModule::kitties_count() {
 		KittiesModule::on_initialize(System::block_number());
 	}
 }
@@ -33,14 +52,22 @@ fn create_kitty_works(){
 		assert_eq!(KittiesModule::kitty_owner(0), Some(1));
 
 		assert_eq!(<UserKitties<Test>>::iter_prefix_values(1).collect::<Vec<KittyIndex>>(),vec![0]);
+		assert_eq!(Balances::reserved_balance(1), 100);
 
 
 		assert_eq!(
 			System::events(),
-			vec![EventRecord {
-				phase: Phase::Initialization,
-				event: TestEvent::kitties_event(RawEvent::KittyCreated(1, 0)),
-				topics: vec![] }
+			vec![
+				EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::pallet_balances(pallet_balances::RawEvent::Reserved(1, 100)),
+					topics: vec![]
+				},
+				EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::kitties_event(RawEvent::KittyCreated(1, 0)),
+					topics: vec![]
+				},
 			]
 		);
 	})
@@ -53,7 +80,7 @@ fn create_kitty_exceed_maximun(){
 		run_to_block(10);
 
 		//模拟kitty数量达到最大的情况
-		KittiesCount::put(KittyIndex::max_value());
+		<KittiesCount<Test>>::put(KittyIndex::max_value());
 		assert_noop!(
 			KittiesModule::create(Origin::signed(1)),
 			Error::<Test>::KittiesCountOverflow
@@ -77,15 +104,29 @@ fn transfer_kitty_works() {
 		assert_eq!(KittiesModule::kitties_count(), 1);
 
 		assert_eq!(KittiesModule::kitty_owner(0), Some(2));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 100);
 
 		assert_eq!(
 			System::events(),
 			vec![
+				EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::pallet_balances(pallet_balances::RawEvent::Reserved(1, 100)),
+					topics: vec![]
+				},
 				EventRecord {
 				phase: Phase::Initialization,
 				event: TestEvent::kitties_event(RawEvent::KittyCreated(1, 0)),
 				topics: vec![]
 			},
+				EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::pallet_balances(pallet_balances::RawEvent::ReserveRepatriated(
+						1, 2, 100, BalanceStatus::Reserved
+					)),
+					topics: vec![]
+				},
 				EventRecord {
 					phase: Phase::Initialization,
 					event: TestEvent::kitties_event(RawEvent::Transferred(1, 2, 0)),
@@ -115,6 +156,7 @@ fn breed_works(){
 		run_to_block(10);
 		assert_ok!(KittiesModule::create(Origin::signed(1)));
 		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		hatch_eggs();
 
 		assert_ok!(
 			KittiesModule::breed(Origin::signed(1),0,1)
@@ -132,20 +174,91 @@ fn breed_works(){
 		assert_eq!(KittiesModule::parents(2,1), 1);
 		assert_eq!(KittiesModule::parents(2,0), 0);
 
-		assert_eq!(Parents::iter_prefix_values(2).collect::<Vec<KittyIndex>>(),vec![0,1]);
+		assert_eq!(<Parents<Test>>::iter_prefix_values(2).collect::<Vec<KittyIndex>>(),vec![0,1]);
 		//对children的测试
 		assert_eq!(KittiesModule::children(0,2), 2);
 		assert_eq!(KittiesModule::children(1,2), 2);
-		assert_eq!(Children::iter_prefix_values(0).collect::<Vec<KittyIndex>>(),vec![2]);
-		assert_eq!(Children::iter_prefix_values(1).collect::<Vec<KittyIndex>>(),vec![2]);
+		assert_eq!(<Children<Test>>::iter_prefix_values(0).collect::<Vec<KittyIndex>>(),vec![2]);
+		assert_eq!(<Children<Test>>::iter_prefix_values(1).collect::<Vec<KittyIndex>>(),vec![2]);
 
 		//对breeded的测试
 		assert_eq!(KittiesModule::breeded(0,1), 1);
 		assert_eq!(KittiesModule::breeded(1,0), 0);
 
-		assert_eq!(Breeded::iter_prefix_values(0).collect::<Vec<KittyIndex>>(),vec![1]);
-		assert_eq!(Breeded::iter_prefix_values(1).collect::<Vec<KittyIndex>>(),vec![0]);
+		assert_eq!(<Breeded<Test>>::iter_prefix_values(0).collect::<Vec<KittyIndex>>(),vec![1]);
+		assert_eq!(<Breeded<Test>>::iter_prefix_values(1).collect::<Vec<KittyIndex>>(),vec![0]);
+
+	})
+}
+
+#[test]
+//转移不属于自己的kitty
+fn transfer_kitty_fails_when_not_owner() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_noop!(
+			KittiesModule::transfer(Origin::signed(2), 3, 0),
+			Error::<Test>::NotKittyOwner
+		);
+	})
+}
+
+#[test]
+//转移kitty给自己
+fn transfer_kitty_fails_when_to_self() {
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_noop!(
+			KittiesModule::transfer(Origin::signed(1), 1, 0),
+			Error::<Test>::TransferToSelf
+		);
+	})
+}
+
+#[test]
+//余额不足以支付押金时创建kitty
+fn create_kitty_fails_when_reserve_too_low(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+
+		assert_noop!(
+			KittiesModule::create(Origin::signed(3)),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+		assert_eq!(KittiesModule::kitties_count(), 0);
+	})
+}
+
+#[test]
+//breed不属于自己的kitty
+fn breed_fails_when_not_owner(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(2)));
+
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), 0, 1),
+			Error::<Test>::NotKittyOwner
+		);
+	})
+}
+
+#[test]
+//breed需要支付押金
+fn breed_reserves_deposit(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		hatch_eggs();
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
 
+		assert_eq!(Balances::reserved_balance(1), 300);
 	})
 }
 
@@ -163,3 +276,1543 @@ fn breed_with_same_parent(){
 		);
 	})
 }
+
+#[test]
+//正常挂单和撤单
+fn ask_works(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(100)));
+		assert_eq!(KittiesModule::kitty_price(0), Some(100));
+
+		assert_ok!(KittiesModule::ask(Origin::signed(1), 0, None));
+		assert_eq!(KittiesModule::kitty_price(0), None);
+	})
+}
+
+#[test]
+//非owner挂单
+fn ask_fails_when_not_owner(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_noop!(
+			KittiesModule::ask(Origin::signed(2), 0, Some(100)),
+			Error::<Test>::NotKittyOwner
+		);
+	})
+}
+
+#[test]
+//正常购买kitty
+fn buy_works(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(100)));
+
+		assert_ok!(KittiesModule::buy(Origin::signed(2), 0, 150));
+
+		assert_eq!(KittiesModule::kitty_owner(0), Some(2));
+		assert_eq!(<UserKitties<Test>>::iter_prefix_values(1).collect::<Vec<KittyIndex>>(), vec![]);
+		assert_eq!(<UserKitties<Test>>::iter_prefix_values(2).collect::<Vec<KittyIndex>>(), vec![0]);
+		assert_eq!(KittiesModule::kitty_price(0), None);
+
+		assert_eq!(Balances::free_balance(1), 10_000);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(2), 9_900);
+		assert_eq!(Balances::reserved_balance(2), 100);
+
+		assert_eq!(
+			System::events().last().map(|e| e.event.clone()),
+			Some(TestEvent::kitties_event(RawEvent::Sold(1, 2, 0, 100)))
+		);
+	})
+}
+
+#[test]
+//购买未挂单的kitty
+fn buy_fails_when_not_for_sale(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_noop!(
+			KittiesModule::buy(Origin::signed(2), 0, 100),
+			Error::<Test>::KittyNotForSale
+		);
+	})
+}
+
+#[test]
+//出价低于挂单价格
+fn buy_fails_when_price_too_low(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(100)));
+
+		assert_noop!(
+			KittiesModule::buy(Origin::signed(2), 0, 99),
+			Error::<Test>::PriceTooLow
+		);
+	})
+}
+
+#[test]
+//余额不足时购买
+fn buy_fails_when_balance_too_low(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(100)));
+
+		assert_noop!(
+			KittiesModule::buy(Origin::signed(3), 0, 100),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+		assert_eq!(KittiesModule::kitty_owner(0), Some(1));
+	})
+}
+
+#[test]
+//查询接口
+fn query_helpers_work(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		hatch_eggs();
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+		hatch_eggs();
+
+		let mut owned = KittiesModule::kitties_of(&1, 0, 10);
+		owned.sort();
+		assert_eq!(owned, vec![0, 1, 2]);
+		assert_eq!(KittiesModule::kitties_of(&2, 0, 10), vec![]);
+
+		assert_eq!(KittiesModule::kitty_dna(2), KittiesModule::kitties(2).map(|k| k.dna));
+		assert_eq!(KittiesModule::kitty_dna(3), None);
+
+		assert_eq!(KittiesModule::parents_of(2), vec![0, 1]);
+		assert_eq!(KittiesModule::children_of(0), vec![2]);
+		assert_eq!(KittiesModule::breeding_partners_of(1), vec![0]);
+		assert_eq!(KittiesModule::parents_of(0), vec![]);
+	})
+}
+
+#[test]
+//DNA解码成基因
+fn genome_decode_works(){
+	let mut dna = [0u8; 16];
+	//体色: Grey(2) 与 White(7), Grey更显性
+	dna[0] = 7;
+	dna[1] = 2 + 8;
+	//眼睛: Sleepy(3) 与 Almond(1)
+	dna[2] = 3;
+	dna[3] = 1;
+	//花纹: Solid(5) 两个等位基因
+	dna[4] = 5;
+	dna[5] = 11;
+	genome::set_generation(&mut dna, 6);
+
+	assert_eq!(
+		Kitty { dna, generation: 6, next_breed_block: 0u64, deposit: 0u64 }.traits(),
+		genome::KittyTraits {
+			body_colour: genome::BodyColour::Grey,
+			eye_shape: genome::EyeShape::Almond,
+			pattern: genome::Pattern::Solid,
+			generation: 6,
+			cooldown_index: 3,
+		}
+	);
+}
+
+#[test]
+//新建kitty为第0代
+fn created_kitty_is_first_generation(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		hatch_eggs();
+
+		let traits = KittiesModule::kitties(0).unwrap().traits();
+		assert_eq!(traits.generation, 0);
+		assert_eq!(traits.cooldown_index, 0);
+	})
+}
+
+#[test]
+//breed时每个基因各从父母继承一个等位基因
+fn genome_breed_inherits_alleles(){
+	let mut dna1 = [0u8; 16];
+	let mut dna2 = [0u8; 16];
+	for i in 0..16 {
+		dna1[i] = i as u8;
+		dna2[i] = 0x80 | i as u8;
+	}
+	genome::set_generation(&mut dna1, 3);
+	genome::set_generation(&mut dna2, 8);
+
+	for seed in 0..=255u8 {
+		let selector = [seed; 16];
+		let child = genome::breed(&dna1, &dna2, &selector);
+
+		for gene in [0usize, 2, 4].iter() {
+			let alleles = [child[*gene], child[*gene + 1]];
+			assert!(alleles.iter().any(|a| dna1[*gene..*gene + 2].contains(a)));
+			assert!(alleles.iter().any(|a| dna2[*gene..*gene + 2].contains(a)));
+		}
+		assert_eq!(genome::generation(&child), 9);
+		assert_eq!(child[8], genome::cooldown_index(9));
+		for i in 9..16 {
+			assert_eq!(child[i], genome::combine_dna(dna1[i], dna2[i], seed));
+		}
+	}
+}
+
+#[test]
+//隐性基因可以在后代中表现
+fn genome_recessive_allele_can_surface(){
+	let mut dna1 = [0u8; 16];
+	let mut dna2 = [0u8; 16];
+	//父母都表现Black(0), 都携带隐性的White(7)
+	dna1[0] = 0;
+	dna1[1] = 7;
+	dna2[0] = 0;
+	dna2[1] = 7;
+
+	let mut selector = [0u8; 16];
+	selector[0] = 0b11;
+	let child = genome::breed(&dna1, &dna2, &selector);
+	assert_eq!(genome::decode(&dna1).body_colour, genome::BodyColour::Black);
+	assert_eq!(genome::decode(&child).body_colour, genome::BodyColour::White);
+}
+
+#[test]
+//breed后父母进入冷却期
+fn breed_fails_when_on_cooldown(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		hatch_eggs();
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+
+		assert_eq!(KittiesModule::kitties(0).unwrap().next_breed_block, 15);
+		assert_eq!(KittiesModule::kitties(1).unwrap().next_breed_block, 15);
+
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), 0, 1),
+			Error::<Test>::KittyOnCooldown
+		);
+
+		run_to_block(14);
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), 1, 0),
+			Error::<Test>::KittyOnCooldown
+		);
+
+		run_to_block(15);
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+	})
+}
+
+#[test]
+//子代的代数为父母最大代数加一, 冷却期随代数增长
+fn breed_tracks_generation(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		hatch_eggs();
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+		hatch_eggs();
+
+		let child = KittiesModule::kitties(3).unwrap();
+		assert_eq!(child.generation, 1);
+		assert_eq!(child.traits().generation, 1);
+		assert_eq!(child.next_breed_block, 15);
+
+		run_to_block(15);
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 2, 3));
+		let grandchild = KittiesModule::kitties(4).unwrap();
+		assert_eq!(grandchild.generation, 2);
+		assert_eq!(grandchild.traits().generation, 2);
+		//第2代的冷却序号为1, 冷却期翻倍
+		assert_eq!(grandchild.next_breed_block, 25);
+	})
+}
+
+#[test]
+//父母与子代不能breed
+fn breed_fails_for_parent_and_child(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		hatch_eggs();
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+
+		run_to_block(15);
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), 0, 2),
+			Error::<Test>::RelatedKitties
+		);
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), 2, 1),
+			Error::<Test>::RelatedKitties
+		);
+	})
+}
+
+#[test]
+//兄弟姐妹不能breed
+fn breed_fails_for_siblings(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		hatch_eggs();
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+
+		run_to_block(15);
+		//同父异母也算兄弟姐妹
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 2));
+
+		run_to_block(20);
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), 3, 4),
+			Error::<Test>::RelatedKitties
+		);
+	})
+}
+
+#[test]
+//共同祖先在MaxInbreedingDepth代以内不能breed
+fn breed_fails_within_inbreeding_depth(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		hatch_eggs();
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+		hatch_eggs();
+
+		run_to_block(15);
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 3, 2));
+
+		//0是4的祖父母
+		run_to_block(25);
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), 0, 4),
+			Error::<Test>::RelatedKitties
+		);
+
+		//与1无关的kitty可以breed
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		hatch_eggs();
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 1, 5));
+	})
+}
+
+#[test]
+//正常销毁kitty
+fn burn_works(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		hatch_eggs();
+		assert_ok!(KittiesModule::ask(Origin::signed(1), 0, Some(100)));
+		assert_eq!(Balances::reserved_balance(1), 100);
+
+		assert_ok!(KittiesModule::burn(Origin::signed(1), 0));
+
+		assert!(KittiesModule::kitties(0).is_none());
+		assert_eq!(KittiesModule::kitty_owner(0), None);
+		assert_eq!(KittiesModule::kitty_price(0), None);
+		assert_eq!(<UserKitties<Test>>::iter_prefix_values(1).collect::<Vec<KittyIndex>>(), vec![]);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		//kitty编号不会被重用
+		assert_eq!(KittiesModule::kitties_count(), 1);
+
+		assert_eq!(
+			System::events().last().map(|e| e.event.clone()),
+			Some(TestEvent::kitties_event(RawEvent::KittyBurned(1, 0)))
+		);
+	})
+}
+
+#[test]
+//销毁不属于自己的kitty
+fn burn_fails_when_not_owner(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_noop!(
+			KittiesModule::burn(Origin::signed(2), 0),
+			Error::<Test>::NotKittyOwner
+		);
+	})
+}
+
+#[test]
+//销毁后保留血缘关系
+fn burn_keeps_lineage(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		hatch_eggs();
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+
+		assert_ok!(KittiesModule::burn(Origin::signed(1), 0));
+
+		assert_eq!(KittiesModule::parents_of(2), vec![0, 1]);
+		assert_eq!(KittiesModule::children_of(0), vec![2]);
+	})
+}
+
+#[test]
+//root强制销毁kitty
+fn force_burn_works(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_noop!(KittiesModule::force_burn(Origin::signed(1), 0), DispatchError::BadOrigin);
+		assert_noop!(
+			KittiesModule::force_burn(Origin::root(), 1),
+			Error::<Test>::InvalidKittyId
+		);
+
+		assert_ok!(KittiesModule::force_burn(Origin::root(), 0));
+		assert!(KittiesModule::kitties(0).is_none());
+		assert_eq!(Balances::reserved_balance(1), 0);
+		//未孵化的蛋一并移除
+		assert_eq!(KittiesModule::egg(0), None);
+		assert_eq!(KittiesModule::eggs_of(1), 0);
+	})
+}
+
+#[test]
+//授权他人转移kitty
+fn approve_and_transfer_from_works(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_noop!(
+			KittiesModule::transfer_from(Origin::signed(2), 1, 3, 0),
+			Error::<Test>::NotApproved
+		);
+
+		assert_ok!(KittiesModule::approve(Origin::signed(1), 0, Some(2)));
+		assert_eq!(KittiesModule::approved(0), Some(2));
+
+		assert_ok!(KittiesModule::transfer_from(Origin::signed(2), 1, 3, 0));
+		assert_eq!(KittiesModule::kitty_owner(0), Some(3));
+		//转移后授权被清除
+		assert_eq!(KittiesModule::approved(0), None);
+
+		assert_eq!(
+			System::events().last().map(|e| e.event.clone()),
+			Some(TestEvent::kitties_event(RawEvent::Transferred(1, 3, 0)))
+		);
+	})
+}
+
+#[test]
+//清除授权
+fn approve_can_be_cleared(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::approve(Origin::signed(1), 0, Some(2)));
+		assert_ok!(KittiesModule::approve(Origin::signed(1), 0, None));
+		assert_eq!(KittiesModule::approved(0), None);
+
+		assert_noop!(
+			KittiesModule::transfer_from(Origin::signed(2), 1, 2, 0),
+			Error::<Test>::NotApproved
+		);
+	})
+}
+
+#[test]
+//非owner不能授权
+fn approve_fails_when_not_owner(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_noop!(
+			KittiesModule::approve(Origin::signed(2), 0, Some(2)),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_noop!(
+			KittiesModule::approve(Origin::signed(1), 0, Some(1)),
+			Error::<Test>::ApproveToSelf
+		);
+	})
+}
+
+#[test]
+//operator可以转移owner的所有kitty
+fn operator_can_transfer_and_approve(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(1), 2, true));
+		assert!(KittiesModule::is_operator(1, 2));
+
+		assert_ok!(KittiesModule::transfer_from(Origin::signed(2), 1, 3, 0));
+		assert_eq!(KittiesModule::kitty_owner(0), Some(3));
+
+		//operator可以替owner授权
+		assert_ok!(KittiesModule::approve(Origin::signed(2), 1, Some(3)));
+		assert_eq!(KittiesModule::approved(1), Some(3));
+
+		assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(1), 2, false));
+		assert!(!KittiesModule::is_operator(1, 2));
+		assert_noop!(
+			KittiesModule::transfer_from(Origin::signed(2), 1, 2, 1),
+			Error::<Test>::NotApproved
+		);
+	})
+}
+
+#[test]
+//from不是owner
+fn transfer_from_fails_when_from_not_owner(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_noop!(
+			KittiesModule::transfer_from(Origin::signed(2), 2, 3, 0),
+			Error::<Test>::NotKittyOwner
+		);
+	})
+}
+
+#[test]
+//创世时预先创建kitty
+fn genesis_kitties_work(){
+	let mut dna = [0u8; 16];
+	dna[0] = 7;
+	new_test_ext_with_kitties(vec![(1, None), (2, Some(dna)), (1, None)]).execute_with(|| {
+		assert_eq!(KittiesModule::kitties_count(), 3);
+		assert_eq!(KittiesModule::kitty_owner(0), Some(1));
+		assert_eq!(KittiesModule::kitty_owner(1), Some(2));
+		assert_eq!(KittiesModule::kitty_owner(2), Some(1));
+
+		assert_eq!(KittiesModule::kitty_dna(1), Some(dna));
+		//未指定DNA时由owner和编号确定
+		assert_eq!(
+			KittiesModule::kitty_dna(0),
+			Some(genome::new_dna((1u64, 0u32).using_encoded(blake2_128)))
+		);
+		assert_ne!(KittiesModule::kitty_dna(0), KittiesModule::kitty_dna(2));
+		assert_eq!(KittiesModule::kitties(2).unwrap().generation, 0);
+		assert_eq!(KittiesModule::next_to_hatch(), 3);
+
+		assert_eq!(Balances::reserved_balance(1), 200);
+		assert_eq!(Balances::reserved_balance(2), 100);
+	})
+}
+
+#[test]
+//正常拍卖kitty
+fn auction_works(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 200, 5));
+		assert_eq!(KittiesModule::auction(0).map(|a| a.end), Some(15));
+
+		assert_ok!(KittiesModule::bid(Origin::signed(2), 0, 200));
+		assert_eq!(Balances::reserved_balance(2), 200);
+
+		//更高的出价释放之前的出价
+		let _ = Balances::deposit_creating(&4, 10_000);
+		assert_ok!(KittiesModule::bid(Origin::signed(4), 0, 300));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::reserved_balance(4), 300);
+
+		//结束前kitty不会转移
+		run_to_block(15);
+		assert_eq!(KittiesModule::kitty_owner(0), Some(1));
+
+		run_to_block(16);
+		assert_eq!(KittiesModule::kitty_owner(0), Some(4));
+		assert!(KittiesModule::auction(0).is_none());
+		assert_eq!(Balances::free_balance(1), 10_000 - 100 + 300);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		//买家只保留kitty的押金
+		assert_eq!(Balances::free_balance(4), 10_000 - 300);
+		assert_eq!(Balances::reserved_balance(4), 100);
+
+		assert!(System::events().iter().any(|e|
+			e.event == TestEvent::kitties_event(RawEvent::AuctionWon(1, 4, 0, 300))
+		));
+	})
+}
+
+#[test]
+//无人出价的拍卖
+fn auction_without_bids_is_unsold(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 200, 5));
+
+		run_to_block(16);
+		assert_eq!(KittiesModule::kitty_owner(0), Some(1));
+		assert!(KittiesModule::auction(0).is_none());
+		assert_eq!(
+			System::events().last().map(|e| e.event.clone()),
+			Some(TestEvent::kitties_event(RawEvent::AuctionUnsold(1, 0)))
+		);
+
+		//拍卖结束后可以正常转移
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, 0));
+	})
+}
+
+#[test]
+//拍卖中的kitty被锁定
+fn auction_locks_kitty(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 200, 5));
+
+		assert_noop!(KittiesModule::transfer(Origin::signed(1), 2, 0), Error::<Test>::KittyInAuction);
+		assert_noop!(KittiesModule::breed(Origin::signed(1), 0, 1), Error::<Test>::KittyInAuction);
+		assert_noop!(KittiesModule::ask(Origin::signed(1), 0, Some(100)), Error::<Test>::KittyInAuction);
+		assert_noop!(KittiesModule::burn(Origin::signed(1), 0), Error::<Test>::KittyInAuction);
+		assert_noop!(
+			KittiesModule::start_auction(Origin::signed(1), 0, 200, 5),
+			Error::<Test>::KittyInAuction
+		);
+	})
+}
+
+#[test]
+//拍卖参数及出价检查
+fn auction_rejects_invalid_calls(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_noop!(
+			KittiesModule::start_auction(Origin::signed(2), 0, 200, 5),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_noop!(
+			KittiesModule::start_auction(Origin::signed(1), 0, 200, 0),
+			Error::<Test>::InvalidAuctionDuration
+		);
+		assert_noop!(
+			KittiesModule::start_auction(Origin::signed(1), 0, 200, 21),
+			Error::<Test>::InvalidAuctionDuration
+		);
+		assert_noop!(KittiesModule::bid(Origin::signed(2), 0, 200), Error::<Test>::NotInAuction);
+
+		assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 200, 5));
+		assert_noop!(KittiesModule::bid(Origin::signed(1), 0, 200), Error::<Test>::BidOnOwnAuction);
+		assert_noop!(KittiesModule::bid(Origin::signed(2), 0, 199), Error::<Test>::BidTooLow);
+		assert_ok!(KittiesModule::bid(Origin::signed(2), 0, 200));
+		assert_noop!(KittiesModule::bid(Origin::signed(2), 0, 200), Error::<Test>::BidTooLow);
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(3), 0, 250),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+	})
+}
+
+#[test]
+//出价未足额保留时流拍而不是少付给卖家
+fn auction_is_unsold_when_bid_not_reserved(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 200, 5));
+		assert_ok!(KittiesModule::bid(Origin::signed(2), 0, 200));
+		Balances::unreserve(&2, 150);
+
+		run_to_block(16);
+		assert_eq!(KittiesModule::kitty_owner(0), Some(1));
+		assert_eq!(Balances::free_balance(1), 10_000 - 100);
+		assert_eq!(Balances::reserved_balance(1), 100);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(KittiesModule::owned_kitties_count(2), 0);
+		assert_eq!(
+			System::events().last().map(|e| e.event.clone()),
+			Some(TestEvent::kitties_event(RawEvent::AuctionUnsold(1, 0)))
+		);
+	})
+}
+
+#[test]
+//同一区块结束的拍卖数量有上限
+fn auctions_ending_per_block_are_bounded(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		for _ in 0..5 {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+		}
+		assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 200, 5));
+		run_to_block(11);
+		assert_ok!(KittiesModule::start_auction(Origin::signed(1), 1, 200, 4));
+		assert_ok!(KittiesModule::start_auction(Origin::signed(1), 2, 200, 4));
+		assert_eq!(KittiesModule::auctions_ending_count(15), 3);
+		assert_noop!(
+			KittiesModule::start_auction(Origin::signed(1), 3, 200, 4),
+			Error::<Test>::TooManyAuctionsEnding
+		);
+		assert_ok!(KittiesModule::start_auction(Origin::signed(1), 3, 200, 5));
+
+		//取消的拍卖释放名额
+		assert_ok!(KittiesModule::force_burn(Origin::root(), 0));
+		assert_eq!(KittiesModule::auctions_ending_count(15), 2);
+		assert_ok!(KittiesModule::start_auction(Origin::signed(1), 4, 200, 4));
+
+		run_to_block(16);
+		assert_eq!(KittiesModule::auctions_ending_count(15), 0);
+		assert_eq!(<AuctionsEnding<Test>>::iter_prefix_values(15).count(), 0);
+		assert_eq!(KittiesModule::auctions_ending_count(16), 1);
+	})
+}
+
+#[test]
+//强制销毁拍卖中的kitty退还出价
+fn force_burn_cancels_auction(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 200, 5));
+		assert_ok!(KittiesModule::bid(Origin::signed(2), 0, 200));
+
+		assert_ok!(KittiesModule::force_burn(Origin::root(), 0));
+		assert!(KittiesModule::auction(0).is_none());
+		assert_eq!(Balances::reserved_balance(2), 0);
+
+		run_to_block(16);
+		assert_eq!(KittiesModule::kitty_owner(0), None);
+	})
+}
+
+#[test]
+//租用他人的kitty繁殖
+fn breed_with_sire_works(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(2)));
+		hatch_eggs();
+		assert_ok!(KittiesModule::set_sire_price(Origin::signed(2), 1, Some(50)));
+		assert_eq!(KittiesModule::sire_price(1), Some(50));
+
+		assert_ok!(KittiesModule::breed_with_sire(Origin::signed(1), 0, 1, 60));
+
+		assert_eq!(KittiesModule::kitty_owner(1), Some(2));
+		assert_eq!(KittiesModule::kitty_owner(2), Some(1));
+		assert_eq!(KittiesModule::parents_of(2).len(), 2);
+		assert_eq!(KittiesModule::sire_price(1), Some(50));
+
+		assert_eq!(Balances::free_balance(1), 9_750);
+		assert_eq!(Balances::reserved_balance(1), 200);
+		assert_eq!(Balances::free_balance(2), 9_950);
+		assert_eq!(Balances::reserved_balance(2), 100);
+
+		assert_eq!(
+			System::events().last().map(|e| e.event.clone()),
+			Some(TestEvent::kitties_event(RawEvent::SireRented(1, 2, 1, 2, 50)))
+		);
+	})
+}
+
+#[test]
+//租用繁殖的失败情况
+fn breed_with_sire_fails(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(2)));
+
+		assert_noop!(
+			KittiesModule::breed_with_sire(Origin::signed(1), 0, 2, 100),
+			Error::<Test>::SireNotForRent
+		);
+
+		assert_ok!(KittiesModule::set_sire_price(Origin::signed(2), 2, Some(50)));
+		assert_noop!(
+			KittiesModule::breed_with_sire(Origin::signed(1), 0, 2, 40),
+			Error::<Test>::PriceTooLow
+		);
+		assert_noop!(
+			KittiesModule::breed_with_sire(Origin::signed(2), 0, 2, 50),
+			Error::<Test>::NotKittyOwner
+		);
+
+		assert_ok!(KittiesModule::set_sire_price(Origin::signed(1), 1, Some(50)));
+		assert_noop!(
+			KittiesModule::breed_with_sire(Origin::signed(1), 0, 1, 50),
+			Error::<Test>::RentFromSelf
+		);
+		assert_noop!(
+			KittiesModule::set_sire_price(Origin::signed(2), 0, Some(50)),
+			Error::<Test>::NotKittyOwner
+		);
+	})
+}
+
+#[test]
+//kitty转移后租用报价失效
+fn sire_price_cleared_on_transfer(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::set_sire_price(Origin::signed(1), 0, Some(50)));
+
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, 0));
+		assert_eq!(KittiesModule::sire_price(0), None);
+	})
+}
+
+#[test]
+//每个账户拥有的kitty数量有上限
+fn create_fails_when_too_many_kitties(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		for _ in 0..MaxKittiesPerAccount::get() {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			hatch_eggs();
+		}
+		assert_eq!(KittiesModule::owned_kitties_count(1), 20);
+
+		assert_noop!(
+			KittiesModule::create(Origin::signed(1)),
+			Error::<Test>::TooManyKitties
+		);
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), 0, 1),
+			Error::<Test>::TooManyKitties
+		);
+
+		assert_ok!(KittiesModule::create(Origin::signed(2)));
+		assert_noop!(
+			KittiesModule::transfer(Origin::signed(2), 1, 20),
+			Error::<Test>::TooManyKitties
+		);
+
+		//销毁后可以再次创建
+		assert_ok!(KittiesModule::burn(Origin::signed(1), 0));
+		assert_eq!(KittiesModule::owned_kitties_count(1), 19);
+		assert_ok!(KittiesModule::transfer(Origin::signed(2), 1, 20));
+		assert_eq!(KittiesModule::owned_kitties_count(1), 20);
+		assert_eq!(KittiesModule::owned_kitties_count(2), 0);
+	})
+}
+
+#[test]
+//分页查询拥有的kitty
+fn kitties_of_pages_through_owned_kitties(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		for _ in 0..5 {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+		}
+
+		let all = KittiesModule::kitties_of(&1, 0, 10);
+		assert_eq!(all.len(), 5);
+
+		let mut paged = KittiesModule::kitties_of(&1, 0, 2);
+		paged.extend(KittiesModule::kitties_of(&1, 2, 2));
+		paged.extend(KittiesModule::kitties_of(&1, 4, 2));
+		assert_eq!(paged, all);
+
+		assert_eq!(KittiesModule::kitties_of(&1, 5, 2), vec![]);
+		assert_eq!(KittiesModule::kitties_of(&1, 0, 0), vec![]);
+	})
+}
+
+#[test]
+//拍卖赢家已达到上限时拍卖流拍并退还出价
+fn auction_unsold_when_winner_has_too_many_kitties(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 200, 5));
+		assert_ok!(KittiesModule::bid(Origin::signed(2), 0, 200));
+
+		for _ in 0..MaxKittiesPerAccount::get() {
+			assert_ok!(KittiesModule::create(Origin::signed(2)));
+			hatch_eggs();
+		}
+
+		run_to_block(16);
+		assert_eq!(KittiesModule::kitty_owner(0), Some(1));
+		assert_eq!(Balances::reserved_balance(2), 2_000);
+		assert_eq!(Balances::free_balance(1), 10_000 - 100);
+		assert!(System::events().iter().any(|e|
+			e.event == TestEvent::kitties_event(RawEvent::AuctionUnsold(1, 0))
+		));
+	})
+}
+
+#[test]
+//从旧的存储格式迁移
+fn migration_to_v2_works(){
+	use frame_support::{
+		Blake2_128Concat, StorageHasher,
+		storage::migration::put_storage_value,
+		traits::OnRuntimeUpgrade,
+	};
+	use crate::migration::OldKitty;
+
+	new_test_ext().execute_with(|| {
+		let put_old_kitty = |index: u32, dna: [u8; 16], owner: u64| {
+			put_storage_value(b"Kitties", b"Kitties", &Blake2_128Concat::hash(&index.encode()), OldKitty(dna));
+			<KittyOwners<Test>>::insert(index, owner);
+		};
+		put_old_kitty(0, [1; 16], 1);
+		put_old_kitty(1, [2; 16], 1);
+		put_old_kitty(2, [3; 16], 2);
+		put_old_kitty(3, [4; 16], 3);
+		<Parents<Test>>::insert(2, 0, 0);
+		<Parents<Test>>::insert(2, 1, 1);
+		put_storage_value(b"Kitties", b"KittiesCount", &[], 4u32);
+		//旧的transfer不会移除原主人的记录
+		<UserKitties<Test>>::insert(1, 2, 2);
+		<UserKitties<Test>>::insert(2, 2, 2);
+		StorageVersion::put(Releases::V1_0_0);
+
+		KittiesModule::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get(), Releases::V2_0_0);
+		assert_eq!(KittiesModule::next_to_hatch(), 4);
+		assert_eq!(KittiesModule::kitties(0).map(|k| k.generation), Some(0));
+		let kitty = KittiesModule::kitties(2).unwrap();
+		assert_eq!(kitty.generation, 1);
+		assert_eq!(kitty.next_breed_block, 0);
+		assert_eq!(kitty.dna[..6], [3; 6]);
+		assert_eq!(genome::generation(&kitty.dna), 1);
+
+		let mut owned = KittiesModule::kitties_of(&1, 0, 10);
+		owned.sort();
+		assert_eq!(owned, vec![0, 1]);
+		assert_eq!(KittiesModule::kitties_of(&2, 0, 10), vec![2]);
+		assert_eq!(KittiesModule::owned_kitties_count(1), 2);
+		assert_eq!(KittiesModule::owned_kitties_count(2), 1);
+		assert_eq!(KittiesModule::owned_kitties_count(3), 1);
+
+		assert_eq!(Balances::reserved_balance(1), 200);
+		assert_eq!(Balances::reserved_balance(2), 100);
+		//余额不足的主人保留kitty但没有押金
+		assert_eq!(Balances::reserved_balance(3), 0);
+		assert_eq!(KittiesModule::kitty_owner(3), Some(3));
+		assert_eq!(KittiesModule::kitties(3).map(|k| k.deposit), Some(0));
+		assert_eq!(KittiesModule::kitties(0).map(|k| k.deposit), Some(100));
+
+		//只迁移一次
+		KittiesModule::on_runtime_upgrade();
+		assert_eq!(Balances::reserved_balance(1), 200);
+
+		//没有押金的kitty不会动用主人的其他保留资金
+		let _ = Balances::deposit_creating(&3, 1_000);
+		assert_ok!(Balances::reserve(&3, 50));
+		assert_ok!(KittiesModule::transfer(Origin::signed(3), 2, 3));
+		assert_eq!(Balances::reserved_balance(3), 50);
+		assert_eq!(Balances::reserved_balance(2), 100);
+		assert_ok!(KittiesModule::burn(Origin::signed(2), 3));
+		assert_eq!(Balances::reserved_balance(2), 100);
+	})
+}
+
+#[test]
+//设置kitty的名字和图片
+fn set_metadata_works(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_ok!(KittiesModule::set_metadata(Origin::signed(1), 0, b"Tom".to_vec(), b"ipfs://a".to_vec()));
+		assert_eq!(
+			KittiesModule::kitty_metadata(0),
+			Some(Metadata { name: b"Tom".to_vec(), uri: b"ipfs://a".to_vec(), deposit: 22 })
+		);
+		assert_eq!(Balances::reserved_balance(1), 122);
+		assert_eq!(
+			System::events().last().map(|e| e.event.clone()),
+			Some(TestEvent::kitties_event(RawEvent::MetadataSet(1, 0)))
+		);
+
+		//更短的名字退还部分押金
+		assert_ok!(KittiesModule::set_metadata(Origin::signed(1), 0, b"T".to_vec(), vec![]));
+		assert_eq!(Balances::reserved_balance(1), 102);
+
+		assert_ok!(KittiesModule::clear_metadata(Origin::signed(1), 0));
+		assert_eq!(KittiesModule::kitty_metadata(0), None);
+		assert_eq!(Balances::reserved_balance(1), 100);
+		assert_noop!(
+			KittiesModule::clear_metadata(Origin::signed(1), 0),
+			Error::<Test>::NoMetadata
+		);
+	})
+}
+
+#[test]
+//设置名字和图片的失败情况
+fn set_metadata_fails(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_noop!(
+			KittiesModule::set_metadata(Origin::signed(2), 0, b"Tom".to_vec(), vec![]),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_noop!(
+			KittiesModule::set_metadata(Origin::signed(1), 0, vec![b'n'; 9], vec![]),
+			Error::<Test>::NameTooLong
+		);
+		assert_noop!(
+			KittiesModule::set_metadata(Origin::signed(1), 0, vec![], vec![b'u'; 17]),
+			Error::<Test>::UriTooLong
+		);
+
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 3, 0));
+		let _ = Balances::deposit_creating(&3, 10);
+		assert_noop!(
+			KittiesModule::set_metadata(Origin::signed(3), 0, vec![b'n'; 8], vec![b'u'; 16]),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+	})
+}
+
+#[test]
+//拍卖中的kitty不能修改或清除名字
+fn metadata_is_locked_in_auction(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::set_metadata(Origin::signed(1), 0, b"Tom".to_vec(), vec![]));
+		assert_ok!(KittiesModule::start_auction(Origin::signed(1), 0, 200, 5));
+
+		assert_noop!(
+			KittiesModule::set_metadata(Origin::signed(1), 0, b"Jerry".to_vec(), vec![]),
+			Error::<Test>::KittyInAuction
+		);
+		assert_noop!(
+			KittiesModule::clear_metadata(Origin::signed(1), 0),
+			Error::<Test>::KittyInAuction
+		);
+		assert_eq!(KittiesModule::kitty_metadata(0).map(|m| m.name), Some(b"Tom".to_vec()));
+	})
+}
+
+#[test]
+//名字和押金随kitty转移,销毁时退还
+fn metadata_moves_with_kitty_and_is_refunded_on_burn(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		hatch_eggs();
+		assert_ok!(KittiesModule::set_metadata(Origin::signed(1), 0, b"Tom".to_vec(), vec![]));
+
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, 0));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 106);
+		assert_eq!(KittiesModule::kitty_metadata(0).map(|m| m.name), Some(b"Tom".to_vec()));
+
+		assert_ok!(KittiesModule::burn(Origin::signed(2), 0));
+		assert_eq!(KittiesModule::kitty_metadata(0), None);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 10_000);
+	})
+}
+
+/// Reproducible pseudo random numbers for the property tests below.
+struct Lcg(u64);
+
+impl Lcg {
+	fn next(&mut self, bound: u64) -> u64 {
+		self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+		(self.0 >> 33) % bound
+	}
+
+	fn seed(&mut self) -> H256 {
+		H256::from_low_u64_be(self.next(u64::max_value()))
+	}
+}
+
+#[test]
+//后代DNA的每一位都来自父母之一
+fn child_dna_comes_from_parents(){
+	let mut rng = Lcg(17);
+	for _ in 0..64 {
+		new_test_ext().execute_with(|| {
+			run_to_block(10);
+			close_round(rng.seed(), 0);
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			hatch_eggs();
+			assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+			hatch_eggs();
+
+			let dna1 = KittiesModule::kitty_dna(0).unwrap();
+			let dna2 = KittiesModule::kitty_dna(1).unwrap();
+			let child = KittiesModule::kitty_dna(2).unwrap();
+
+			for gene in [0usize, 2, 4].iter() {
+				let (from1, from2) = (&dna1[*gene..*gene + 2], &dna2[*gene..*gene + 2]);
+				let (a, b) = (child[*gene], child[*gene + 1]);
+				assert!((from1.contains(&a) && from2.contains(&b)) || (from1.contains(&b) && from2.contains(&a)));
+			}
+			assert_eq!(genome::generation(&child), 1);
+			for i in 9..16 {
+				assert_eq!((child[i] ^ dna1[i]) & (child[i] ^ dna2[i]), 0);
+			}
+		});
+	}
+}
+
+#[test]
+//breed之后揭示相同的随机种子得到相同的后代
+fn breeding_is_reproducible_for_a_seed(){
+	let breed_with_seed = |seed: u64| new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(2)));
+		assert_ok!(KittiesModule::transfer(Origin::signed(2), 1, 1));
+		hatch_eggs();
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+		close_round(H256::from_low_u64_be(seed), 11);
+		run_to_block(11);
+		KittiesModule::kitty_dna(2).unwrap()
+	});
+
+	assert_eq!(breed_with_seed(1), breed_with_seed(1));
+	assert_ne!(breed_with_seed(1), breed_with_seed(2));
+}
+
+#[test]
+//breed时已知的随机种子无法决定后代DNA
+fn child_dna_depends_on_later_randomness(){
+	let breed_then_reveal = |revealed: u64| new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		hatch_eggs();
+
+		//breed时的种子对所有人已知
+		close_round(H256::from_low_u64_be(42), 10);
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+		assert_eq!(KittiesModule::kitty_dna(2), None);
+		assert_eq!(KittiesModule::kitties(2).unwrap().generation, 1);
+
+		//种子不包含之后的承诺时不会孵化
+		run_to_block(12);
+		assert_eq!(KittiesModule::kitty_dna(2), None);
+
+		close_round(H256::from_low_u64_be(revealed), 11);
+		run_to_block(13);
+		let dna = KittiesModule::kitty_dna(2).unwrap();
+		assert_eq!(genome::generation(&dna), 1);
+		assert_eq!(KittiesModule::next_to_hatch(), 3);
+		assert_eq!(
+			System::events().last().map(|e| e.event.clone()),
+			Some(TestEvent::kitties_event(RawEvent::KittyHatched(2, dna)))
+		);
+		dna
+	});
+
+	assert_ne!(breed_then_reveal(1), breed_then_reveal(2));
+}
+
+#[test]
+//只用产蛋之后的承诺孵化, 没有揭示时使用父区块哈希
+fn eggs_hatch_from_commitments_made_after_them(){
+	new_test_ext().execute_with(|| {
+		let (early, late) = (H256::repeat_byte(1), H256::repeat_byte(2));
+		run_to_block(5);
+		assert_ok!(CommitReveal::commit(Origin::signed(2), CommitReveal::commitment_of(&2, &early)));
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		//产蛋之前的承诺揭示后不能孵化
+		run_to_block(50);
+		assert_ok!(CommitReveal::reveal(Origin::signed(2), early));
+		run_to_block(60);
+		assert_ok!(CommitReveal::commit(Origin::signed(2), CommitReveal::commitment_of(&2, &late)));
+		run_to_block(100);
+		assert_eq!(CommitReveal::seed_since(), 0);
+		assert_eq!(KittiesModule::kitty_dna(0), None);
+
+		assert_ok!(CommitReveal::reveal(Origin::signed(2), late));
+		run_to_block(120);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		run_to_block(150);
+		assert_eq!(CommitReveal::seed_since(), 50);
+		assert!(KittiesModule::kitty_dna(0).is_some());
+		assert_eq!(KittiesModule::kitty_dna(1), None);
+
+		//没有人揭示时也会孵化
+		run_to_block(200);
+		assert_eq!(KittiesModule::kitty_dna(1), None);
+		run_to_block(250);
+		assert_eq!(CommitReveal::seed_since(), 150);
+		assert!(KittiesModule::kitty_dna(1).is_some());
+		assert!(System::events().iter().any(|e|
+			e.event == TestEvent::pallet_commit_reveal(pallet_commit_reveal::RawEvent::RoundClosed(150, 0))
+		));
+	})
+}
+
+#[test]
+//未孵化的kitty不能breed
+fn breed_fails_when_not_hatched(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), 0, 1),
+			Error::<Test>::KittyNotHatched
+		);
+		hatch_eggs();
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+	})
+}
+
+#[test]
+//每个账户等待孵化的蛋数量有上限
+fn create_fails_when_too_many_eggs(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		for _ in 0..MaxEggsPerAccount::get() {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+		}
+
+		assert_noop!(
+			KittiesModule::create(Origin::signed(1)),
+			Error::<Test>::TooManyEggs
+		);
+		assert_ok!(KittiesModule::create(Origin::signed(2)));
+
+		//转移的蛋仍然计入产蛋的账户
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, 0));
+		assert_noop!(
+			KittiesModule::create(Origin::signed(1)),
+			Error::<Test>::TooManyEggs
+		);
+
+		hatch_eggs();
+		assert_eq!(KittiesModule::eggs_of(1), 0);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+	})
+}
+
+#[test]
+//未孵化的kitty不能销毁, 孵化时跳过强制销毁的蛋
+fn burn_fails_when_not_hatched(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_noop!(
+			KittiesModule::burn(Origin::signed(1), 0),
+			Error::<Test>::KittyNotHatched
+		);
+		assert_ok!(KittiesModule::force_burn(Origin::root(), 0));
+
+		hatch_eggs();
+		assert!(KittiesModule::kitty_dna(1).is_some());
+		assert_eq!(KittiesModule::next_to_hatch(), 2);
+		assert_ok!(KittiesModule::burn(Origin::signed(1), 1));
+	})
+}
+
+#[test]
+//每个区块孵化的蛋数量有上限
+fn hatching_is_bounded_per_block(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		for _ in 0..MaxEggsPerAccount::get() {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+			assert_ok!(KittiesModule::create(Origin::signed(2)));
+		}
+
+		close_round(H256::repeat_byte(1), 11);
+		run_to_block(11);
+		assert_eq!(KittiesModule::next_to_hatch(), MaxHatchPerBlock::get());
+		assert!(KittiesModule::kitty_dna(MaxHatchPerBlock::get() - 1).is_some());
+		assert_eq!(KittiesModule::kitty_dna(MaxHatchPerBlock::get()), None);
+
+		run_to_block(12);
+		assert_eq!(KittiesModule::next_to_hatch(), 20);
+		assert!(KittiesModule::kitty_dna(19).is_some());
+	})
+}
+
+/// Check that counters, ownership indices and deposits agree with each other.
+fn assert_consistent() {
+	let count = KittiesModule::kitties_count();
+	assert_eq!(<Kitties<Test>>::iter().count() as u32, count);
+
+	let mut owned = 0;
+	for account in [1u64, 2, 3].iter() {
+		let kitties = KittiesModule::kitties_of(account, 0, u32::max_value());
+		assert_eq!(kitties.len() as u32, KittiesModule::owned_kitties_count(account));
+		assert_eq!(Balances::reserved_balance(account), 100 * kitties.len() as u64);
+		for kitty_id in kitties.iter() {
+			assert_eq!(KittiesModule::kitty_owner(kitty_id), Some(*account));
+		}
+		owned += kitties.len() as u32;
+	}
+	assert_eq!(owned, count);
+}
+
+#[test]
+//随机的创建、转移、繁殖序列后计数和索引保持一致
+fn random_operations_keep_indices_consistent(){
+	for run in 0..20 {
+		let mut rng = Lcg(run);
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+			for _ in 0..60 {
+				close_round(rng.seed(), System::block_number());
+				let account = 1 + rng.next(3);
+				let count = KittiesModule::kitties_count() as u64;
+				match rng.next(4) {
+					0 => {
+						let _ = KittiesModule::create(Origin::signed(account));
+					}
+					1 if count > 0 => {
+						let kitty_id = rng.next(count) as u32;
+						let owner = KittiesModule::kitty_owner(kitty_id).unwrap();
+						let _ = KittiesModule::transfer(Origin::signed(owner), account, kitty_id);
+					}
+					2 if count > 1 => {
+						let kitty_id_1 = rng.next(count) as u32;
+						let kitty_id_2 = rng.next(count) as u32;
+						let owner = KittiesModule::kitty_owner(kitty_id_1).unwrap();
+						let _ = KittiesModule::breed(Origin::signed(owner), kitty_id_1, kitty_id_2);
+					}
+					_ => run_to_block(System::block_number() + rng.next(10)),
+				}
+				assert_consistent();
+			}
+		});
+	}
+}
+
+#[test]
+//批量创建kitty
+fn create_batch_works(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create_batch(Origin::signed(1), 3));
+		hatch_eggs();
+
+		assert_eq!(KittiesModule::kitties_count(), 3);
+		assert_eq!(KittiesModule::owned_kitties_count(1), 3);
+		assert_eq!(Balances::reserved_balance(1), 300);
+		assert_ne!(KittiesModule::kitty_dna(0), KittiesModule::kitty_dna(1));
+		assert_ne!(KittiesModule::kitty_dna(1), KittiesModule::kitty_dna(2));
+
+		let created = System::events().into_iter()
+			.filter(|e| matches!(e.event, TestEvent::kitties_event(RawEvent::KittyCreated(1, _))))
+			.count();
+		assert_eq!(created, 3);
+	})
+}
+
+#[test]
+//批量创建要么全部成功要么全部失败
+fn create_batch_is_all_or_nothing(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_noop!(
+			KittiesModule::create_batch(Origin::signed(1), MaxBatchSize::get() + 1),
+			Error::<Test>::BatchTooLarge
+		);
+
+		let _ = Balances::deposit_creating(&4, 250);
+		assert_noop!(
+			KittiesModule::create_batch(Origin::signed(4), 3),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+		assert_eq!(KittiesModule::kitties_count(), 0);
+		assert_eq!(Balances::reserved_balance(4), 0);
+	})
+}
+
+#[test]
+//批量转移kitty
+fn transfer_batch_works(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create_batch(Origin::signed(1), 3));
+
+		assert_ok!(KittiesModule::transfer_batch(Origin::signed(1), vec![(2, 0), (3, 1)]));
+
+		assert_eq!(KittiesModule::kitty_owner(0), Some(2));
+		assert_eq!(KittiesModule::kitty_owner(1), Some(3));
+		assert_eq!(KittiesModule::kitty_owner(2), Some(1));
+		assert_eq!(KittiesModule::owned_kitties_count(1), 1);
+		assert_eq!(Balances::reserved_balance(1), 100);
+		assert!(System::events().iter().any(|e|
+			e.event == TestEvent::kitties_event(RawEvent::Transferred(1, 2, 0))
+		));
+		assert!(System::events().iter().any(|e|
+			e.event == TestEvent::kitties_event(RawEvent::Transferred(1, 3, 1))
+		));
+	})
+}
+
+#[test]
+//批量转移中任何一个失败则全部不转移
+fn transfer_batch_is_all_or_nothing(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create_batch(Origin::signed(1), 2));
+		assert_ok!(KittiesModule::create(Origin::signed(2)));
+
+		assert_noop!(
+			KittiesModule::transfer_batch(Origin::signed(1), vec![(3, 0), (3, 2)]),
+			Error::<Test>::NotKittyOwner
+		);
+		assert_noop!(
+			KittiesModule::transfer_batch(Origin::signed(1), vec![(2, 0), (1, 1)]),
+			Error::<Test>::TransferToSelf
+		);
+		assert_noop!(
+			KittiesModule::transfer_batch(Origin::signed(1), vec![(2, 0); 6]),
+			Error::<Test>::BatchTooLarge
+		);
+		assert_eq!(KittiesModule::kitty_owner(0), Some(1));
+		assert_eq!(KittiesModule::owned_kitties_count(1), 2);
+	})
+}
+
+fn breeding_offer(owner: u64, kitty_id: KittyIndex, price: u64, nonce: u32) -> BreedingOffer<u64, KittyIndex, u64, u64> {
+	BreedingOffer { owner, kitty_id, price, expiry: 20, nonce }
+}
+
+fn sign(offer: &BreedingOffer<u64, KittyIndex, u64, u64>) -> TestSignature {
+	TestSignature(offer.owner, KittiesModule::breeding_offer_payload(offer))
+}
+
+#[test]
+//接受链下签名的繁殖报价
+fn accept_breeding_offer_works(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(2)));
+		hatch_eggs();
+		let offer = breeding_offer(2, 1, 50, 7);
+		let other_offer = breeding_offer(2, 1, 60, 3);
+
+		assert_ok!(KittiesModule::accept_breeding_offer(Origin::signed(1), 0, offer.clone(), sign(&offer)));
+
+		assert_eq!(KittiesModule::kitty_owner(2), Some(1));
+		assert_eq!(KittiesModule::kitty_owner(1), Some(2));
+		assert!(KittiesModule::offer_nonce_used(2, 7));
+		assert!(!KittiesModule::offer_nonce_used(2, 3));
+		assert_eq!(Balances::free_balance(1), 10_000 - 200 - 50);
+		assert_eq!(Balances::free_balance(2), 10_000 - 100 + 50);
+		assert_eq!(
+			System::events().last().map(|e| e.event.clone()),
+			Some(TestEvent::kitties_event(RawEvent::SireRented(1, 2, 1, 2, 50)))
+		);
+
+		//同一个报价不能重放
+		run_to_block(20);
+		assert_noop!(
+			KittiesModule::accept_breeding_offer(Origin::signed(1), 0, offer.clone(), sign(&offer)),
+			Error::<Test>::InvalidOfferNonce
+		);
+		//其它报价不受影响
+		assert_ok!(KittiesModule::accept_breeding_offer(Origin::signed(1), 0, other_offer.clone(), sign(&other_offer)));
+		assert_eq!(KittiesModule::kitty_owner(3), Some(1));
+	})
+}
+
+#[test]
+//繁殖报价的签名和有效期检查
+fn accept_breeding_offer_fails(){
+	new_test_ext().execute_with(|| {
+		run_to_block(10);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(2)));
+		let offer = breeding_offer(2, 1, 50, 0);
+
+		//价格被篡改
+		let mut tampered = offer.clone();
+		tampered.price = 1;
+		assert_noop!(
+			KittiesModule::accept_breeding_offer(Origin::signed(1), 0, tampered, sign(&offer)),
+			Error::<Test>::InvalidSignature
+		);
+		//不是kitty主人签名
+		assert_noop!(
+			KittiesModule::accept_breeding_offer(Origin::signed(1), 0, offer.clone(), TestSignature(1, offer.encode())),
+			Error::<Test>::InvalidSignature
+		);
+		//签名必须包含上下文和创世区块哈希
+		assert_noop!(
+			KittiesModule::accept_breeding_offer(Origin::signed(1), 0, offer.clone(), TestSignature(2, offer.encode())),
+			Error::<Test>::InvalidSignature
+		);
+		assert_noop!(
+			KittiesModule::accept_breeding_offer(Origin::signed(2), 0, offer.clone(), sign(&offer)),
+			Error::<Test>::NotKittyOwner
+		);
+		//签名者不再拥有该kitty
+		let not_owned = breeding_offer(2, 0, 50, 1);
+		assert_noop!(
+			KittiesModule::accept_breeding_offer(Origin::signed(1), 0, not_owned.clone(), sign(&not_owned)),
+			Error::<Test>::NotKittyOwner
+		);
+
+		//主人取消未接受的报价
+		assert_ok!(KittiesModule::cancel_breeding_offer(Origin::signed(2), 0));
+		assert_noop!(
+			KittiesModule::accept_breeding_offer(Origin::signed(1), 0, offer.clone(), sign(&offer)),
+			Error::<Test>::InvalidOfferNonce
+		);
+		assert_noop!(
+			KittiesModule::cancel_breeding_offer(Origin::signed(2), 0),
+			Error::<Test>::InvalidOfferNonce
+		);
+		assert_eq!(
+			System::events().last().map(|e| e.event.clone()),
+			Some(TestEvent::kitties_event(RawEvent::BreedingOfferCancelled(2, 0)))
+		);
+
+		let offer = breeding_offer(2, 1, 50, 1);
+		run_to_block(21);
+		assert_noop!(
+			KittiesModule::accept_breeding_offer(Origin::signed(1), 0, offer.clone(), sign(&offer)),
+			Error::<Test>::OfferExpired
+		);
+	})
+}
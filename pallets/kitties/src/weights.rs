@@ -0,0 +1,124 @@
+//! Weights for pallet_kitties.
+//!
+//! These are not measured. Until the benchmarks in `benchmarking.rs` have been run on
+//! reference hardware, `()` charges every call a flat `BASE` plus the storage reads and writes
+//! it makes. Generate measured weights with:
+//!
+//! ```sh
+//! ./target/release/node-template benchmark \
+//! 	--chain dev --execution wasm --wasm-execution compiled \
+//! 	--pallet pallet_kitties --extrinsic '*' --steps 50 --repeat 20 \
+//! 	--output ./pallets/kitties/src/weights.rs
+//! ```
+
+use frame_support::weights::{Weight, constants::RocksDbWeight};
+
+/// Weight functions needed for pallet_kitties.
+pub trait WeightInfo {
+	fn create() -> Weight;
+	fn transfer() -> Weight;
+	fn breed(d: u32, ) -> Weight;
+	fn ask() -> Weight;
+	fn buy() -> Weight;
+	fn approve() -> Weight;
+	fn set_approval_for_all() -> Weight;
+	fn transfer_from() -> Weight;
+	fn burn() -> Weight;
+	fn force_burn() -> Weight;
+	fn start_auction() -> Weight;
+	fn bid() -> Weight;
+	fn settle_auctions(a: u32, ) -> Weight;
+	fn hatch_eggs(n: u32, ) -> Weight;
+	fn set_sire_price() -> Weight;
+	fn breed_with_sire(d: u32, ) -> Weight;
+	fn set_metadata(n: u32, u: u32, ) -> Weight;
+	fn clear_metadata() -> Weight;
+	fn create_batch(n: u32, ) -> Weight;
+	fn transfer_batch(n: u32, ) -> Weight;
+	fn accept_breeding_offer(d: u32, ) -> Weight;
+	fn cancel_breeding_offer() -> Weight;
+}
+
+/// Weight of a call apart from its storage accesses.
+const BASE: Weight = 10_000;
+
+/// `Parents` entries read to look `d` generations back from both parents of a new kitty.
+fn ancestry_reads(d: u32) -> Weight {
+	(2 as Weight).saturating_pow(d.saturating_add(1)).saturating_sub(2).saturating_mul(2)
+}
+
+impl WeightInfo for () {
+	fn create() -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(4, 8))
+	}
+	fn transfer() -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(6, 10))
+	}
+	fn breed(d: u32, ) -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(13, 16))
+			.saturating_add(RocksDbWeight::get().reads(ancestry_reads(d)))
+	}
+	fn ask() -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(1, 1))
+	}
+	fn buy() -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(7, 10))
+	}
+	fn approve() -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(2, 1))
+	}
+	fn set_approval_for_all() -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(0, 1))
+	}
+	fn transfer_from() -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(8, 10))
+	}
+	fn burn() -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(6, 9))
+	}
+	fn force_burn() -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(7, 12))
+	}
+	fn start_auction() -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(3, 5))
+	}
+	fn bid() -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(4, 3))
+	}
+	fn settle_auctions(a: u32, ) -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(1, 2))
+			.saturating_add(RocksDbWeight::get().reads_writes(8, 12).saturating_mul(a as Weight))
+	}
+	fn hatch_eggs(n: u32, ) -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(2, 1))
+			.saturating_add(RocksDbWeight::get().reads_writes(3, 3).saturating_mul(n as Weight))
+	}
+	fn set_sire_price() -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(2, 1))
+	}
+	fn breed_with_sire(d: u32, ) -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(16, 17))
+			.saturating_add(RocksDbWeight::get().reads(ancestry_reads(d)))
+	}
+	fn set_metadata(_n: u32, _u: u32, ) -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(3, 2))
+	}
+	fn clear_metadata() -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(3, 2))
+	}
+	fn create_batch(n: u32, ) -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(3, 2))
+			.saturating_add(RocksDbWeight::get().reads_writes(1, 6).saturating_mul(n as Weight))
+	}
+	fn transfer_batch(n: u32, ) -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(0, 0))
+			.saturating_add(RocksDbWeight::get().reads_writes(7, 10).saturating_mul(n as Weight))
+	}
+	fn accept_breeding_offer(d: u32, ) -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(16, 17))
+			.saturating_add(RocksDbWeight::get().reads(ancestry_reads(d)))
+	}
+	fn cancel_breeding_offer() -> Weight {
+		BASE.saturating_add(RocksDbWeight::get().reads_writes(1, 1))
+	}
+}
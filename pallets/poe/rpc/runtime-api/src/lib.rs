@@ -0,0 +1,39 @@
+//! Runtime API definition for the proof of existence pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Encode, Decode};
+use sp_std::prelude::*;
+
+/// What a verifier needs to certify that a document was notarised.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ClaimInfo<AccountId, BlockNumber, Hash, Moment> {
+	/// Current owner of the claim.
+	pub owner: AccountId,
+	/// Block in which the claim was created.
+	pub block_number: BlockNumber,
+	/// Hash of that block. `None` while it is still the block being built.
+	pub block_hash: Option<Hash>,
+	/// Timestamp of that block.
+	pub timestamp: Moment,
+}
+
+sp_api::decl_runtime_apis! {
+	/// Read-only queries over notarised claims.
+	pub trait PoeApi<AccountId, BlockNumber, Hash, Moment> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Hash: Codec,
+		Moment: Codec,
+	{
+		/// When and by whom the document hashing to `hash` was notarised, if it was.
+		fn claim_info(hash: Hash) -> Option<ClaimInfo<AccountId, BlockNumber, Hash, Moment>>;
+		/// Owners of the claim on `hash` and the blocks they got it in, oldest first. The creator
+		/// is always kept, followed by at most `MaxHistoryLength` later owners.
+		fn claim_history(hash: Hash) -> Vec<(AccountId, BlockNumber)>;
+		/// Whether `proof` shows the document hashing to `leaf` to be in the batch anchored as
+		/// `root`.
+		fn verify_inclusion(root: Hash, leaf: Hash, proof: Vec<Hash>) -> bool;
+	}
+}
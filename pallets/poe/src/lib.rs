@@ -4,14 +4,19 @@
 /// Learn more about FRAME and the core library of Substrate FRAME pallets:
 /// https://substrate.dev/docs/en/knowledgebase/runtime/frame
 
+use codec::{Encode, Decode};
 use frame_support::{
 	decl_module, decl_storage, decl_event, decl_error, dispatch,
-	ensure,
-	traits::Get,
+	ensure, StorageValue,
+	storage::migration::StorageIterator,
+	traits::{Get, Currency, ReservableCurrency, BalanceStatus},
+	weights::Weight,
 };
 use frame_system::ensure_signed;
+use sp_runtime::{RuntimeDebug, traits::{Hash, Saturating, Zero}};
 use sp_std::prelude::*;
 
+pub mod merkle;
 
 #[cfg(test)]
 mod mock;
@@ -19,11 +24,89 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+/// A notarised document, stored under the `T::Hashing` hash of its content.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct Claim<AccountId, BlockNumber, Moment, Balance> {
+	/// Current owner of the claim.
+	pub owner: AccountId,
+	/// Block in which the claim was created. It is kept when the claim changes hands.
+	pub block_number: BlockNumber,
+	/// Timestamp of the block in which the claim was created.
+	pub timestamp: Moment,
+	/// Free text attached by the owner, at most `MaxMemoLength` bytes.
+	pub memo: Option<Vec<u8>>,
+	/// MIME type of the document, at most `MaxMimeTypeLength` bytes.
+	pub mime_type: Option<Vec<u8>>,
+	/// Size of the document in bytes.
+	pub file_size: Option<u64>,
+	/// Reserved from the owner and moving along with the claim.
+	pub deposit: Balance,
+}
+
+/// The root of a Merkle tree over a batch of document hashes, built with `merkle::MerkleTree`.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct Anchor<AccountId, BlockNumber, Moment, Balance> {
+	pub owner: AccountId,
+	/// Block in which the root was anchored.
+	pub block_number: BlockNumber,
+	/// Timestamp of that block.
+	pub timestamp: Moment,
+	/// Number of documents in the batch.
+	pub leaf_count: u32,
+	/// Reserved from the owner until the anchor is revoked.
+	pub deposit: Balance,
+}
+
+// A value placed in storage that represents the current version of the PoE storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	/// Proofs stored as owner and block under the raw document bytes in `Proofs`.
+	V1_0_0,
+	/// Claims stored under the hash of the document in `Claims`.
+	V2_0_0,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1_0_0
+	}
+}
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+
+type ClaimOf<T> = Claim<
+	<T as frame_system::Trait>::AccountId,
+	<T as frame_system::Trait>::BlockNumber,
+	<T as pallet_timestamp::Trait>::Moment,
+	BalanceOf<T>,
+>;
+
+type AnchorOf<T> = Anchor<
+	<T as frame_system::Trait>::AccountId,
+	<T as frame_system::Trait>::BlockNumber,
+	<T as pallet_timestamp::Trait>::Moment,
+	BalanceOf<T>,
+>;
+
 /// Configure the pallet by specifying the parameters and types on which it depends.
-pub trait Trait: frame_system::Trait {
+pub trait Trait: frame_system::Trait + pallet_timestamp::Trait {
 	/// Because this pallet emits events, it depends on the runtime's definition of an event.
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
-	type MaxProofLength: Get<u8>;
+	/// Longest memo attached to a claim, in bytes.
+	type MaxMemoLength: Get<u32>;
+	/// Longest MIME type attached to a claim, in bytes.
+	type MaxMimeTypeLength: Get<u32>;
+	/// The currency in which claim deposits are reserved.
+	type Currency: ReservableCurrency<Self::AccountId>;
+	/// Deposit reserved for every claim and every anchored batch.
+	type ClaimDepositBase: Get<BalanceOf<Self>>;
+	/// Deposit reserved for every byte of a claim's memo and MIME type.
+	type ClaimDepositPerByte: Get<BalanceOf<Self>>;
+	/// Most owners kept in the history of a claim besides its creator, who is always kept. The
+	/// oldest ones after the creator are dropped first.
+	type MaxHistoryLength: Get<u32>;
 }
 
 // The pallet's runtime storage items.
@@ -35,16 +118,44 @@ decl_storage! {
 	trait Store for Module<T: Trait> as PoeModule {
 		// Learn more about declaring storage items:
 		// https://substrate.dev/docs/en/knowledgebase/runtime/storage#declaring-storage-items
-		Proofs get(fn proofs): map hasher(blake2_128_concat) Vec<u8> => (T::AccountId, T::BlockNumber);
+		/// Claims by content hash. A verifier hashes a document with `T::Hashing` and looks the
+		/// hash up here.
+		Claims get(fn claims): map hasher(blake2_128_concat) T::Hash => Option<ClaimOf<T>>;
+		/// Owners of a claim and the blocks they got it in, oldest first. The creator is followed
+		/// by at most `MaxHistoryLength` later owners.
+		ClaimHistory get(fn claim_history):
+			map hasher(blake2_128_concat) T::Hash => Vec<(T::AccountId, T::BlockNumber)>;
+		/// Hashes of the blocks claims and anchors were created in. A block's hash is only known
+		/// from the next block on, when it is copied here from `frame_system`, which forgets it
+		/// after `BlockHashCount` blocks. It is removed with the last claim or anchor of the block.
+		ClaimBlockHashes get(fn claim_block_hashes): map hasher(twox_64_concat) T::BlockNumber => Option<T::Hash>;
+		/// Number of claims and anchors created in a block that have not been revoked yet.
+		ClaimBlockRefs: map hasher(twox_64_concat) T::BlockNumber => u32;
+		/// Anchored Merkle roots of document batches.
+		Anchors get(fn anchors): map hasher(blake2_128_concat) T::Hash => Option<AnchorOf<T>>;
+		/// Block whose hash is to be copied into `ClaimBlockHashes` by the next block.
+		PendingBlockHash: Option<T::BlockNumber>;
+		/// Storage version of the pallet. It is not set at genesis, so a new chain runs the
+		/// migration once, finding no `Proofs` to migrate.
+		StorageVersion: Releases;
 	}
 }
 
 // Pallets use events to inform users when important changes are made.
 // https://substrate.dev/docs/en/knowledgebase/runtime/events
 decl_event!{
-	pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId {
-		ClaimCreated(AccountId, Vec<u8>),
-		ClaimRevoked(AccountId, Vec<u8>),
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Trait>::AccountId,
+		Hash = <T as frame_system::Trait>::Hash,
+	{
+		ClaimCreated(AccountId, Hash),
+		ClaimRevoked(AccountId, Hash),
+		/// A claim changed hands. [from, to, claim]
+		ClaimTransferred(AccountId, AccountId, Hash),
+		/// The Merkle root of a batch of documents was anchored. [who, root, leaf_count]
+		BatchAnchored(AccountId, Hash, u32),
+		/// An anchored Merkle root was revoked. [who, root]
+		AnchorRevoked(AccountId, Hash),
 	}
 }
 
@@ -54,7 +165,16 @@ decl_error! {
 		ProofAlreadyExist,
 		ClaimNotExist,
 		NotClaimOwner,
-		ProofTooLong,
+		MemoTooLong,
+		MimeTypeTooLong,
+		/// The Merkle root is already anchored.
+		AnchorAlreadyExist,
+		/// The Merkle root is not anchored.
+		AnchorNotExist,
+		/// A batch must contain at least one document.
+		EmptyBatch,
+		/// A claim cannot be transferred to its owner.
+		TransferToSelf,
 	}
 }
 
@@ -69,55 +189,228 @@ decl_module! {
 		// Events must be initialized if they are used by the pallet.
 		fn deposit_event() = default;
 
-		#[weight = 0]
-		pub fn create_claim(origin, claim: Vec<u8>) -> dispatch::DispatchResult{
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get() == Releases::V1_0_0 {
+				let weight = Self::migrate_to_v2();
+				StorageVersion::put(Releases::V2_0_0);
+				weight
+			} else {
+				0
+			}
+		}
+
+		fn on_initialize(_now: T::BlockNumber) -> Weight {
+			match PendingBlockHash::<T>::take() {
+				// Everything created in the block may have been revoked in it already.
+				Some(block) if ClaimBlockRefs::<T>::contains_key(block) => {
+					ClaimBlockHashes::<T>::insert(block, frame_system::Module::<T>::block_hash(block));
+					T::DbWeight::get().reads_writes(3, 2)
+				}
+				Some(_) => T::DbWeight::get().reads_writes(2, 1),
+				None => T::DbWeight::get().reads(1),
+			}
+		}
+
+		/// Claim the document whose content hashes to `hash`, optionally describing it.
+		/// `ClaimDepositBase` plus `ClaimDepositPerByte` for every byte of `memo` and
+		/// `mime_type` is reserved until the claim is revoked.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(4, 5)]
+		pub fn create_claim(
+			origin,
+			hash: T::Hash,
+			memo: Option<Vec<u8>>,
+			mime_type: Option<Vec<u8>>,
+			file_size: Option<u64>,
+		) -> dispatch::DispatchResult{
 			let sender = ensure_signed(origin)?;
 
-			ensure!(claim.len() <= T::MaxProofLength::get().into(), Error::<T>::ProofTooLong);
+			ensure!(
+				memo.as_ref().map_or(true, |m| m.len() <= T::MaxMemoLength::get() as usize),
+				Error::<T>::MemoTooLong
+			);
+			ensure!(
+				mime_type.as_ref().map_or(true, |m| m.len() <= T::MaxMimeTypeLength::get() as usize),
+				Error::<T>::MimeTypeTooLong
+			);
+			ensure!(!Claims::<T>::contains_key(&hash), Error::<T>::ProofAlreadyExist);
+
+			let bytes = memo.as_ref().map_or(0, |m| m.len()) + mime_type.as_ref().map_or(0, |m| m.len());
+			let deposit = T::ClaimDepositBase::get()
+				.saturating_add(T::ClaimDepositPerByte::get().saturating_mul((bytes as u32).into()));
+			T::Currency::reserve(&sender, deposit)?;
+
+			let now = frame_system::Module::<T>::block_number();
+			Self::record_owner(&hash, sender.clone(), now);
+			Self::retain_block_hash(now);
+			Claims::<T>::insert(&hash, Claim {
+				owner: sender.clone(),
+				block_number: now,
+				timestamp: pallet_timestamp::Module::<T>::get(),
+				memo,
+				mime_type,
+				file_size,
+				deposit,
+			});
+
+			Self::deposit_event(RawEvent::ClaimCreated(sender, hash));
+
+			Ok(())
+		}
+
+		/// Revoke one of your claims and get its deposit back.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(3, 5)]
+		pub fn revoke_claim(origin, hash: T::Hash) -> dispatch::DispatchResult{
+			let sender = ensure_signed(origin)?;
 
-			ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+			let claim = Claims::<T>::get(&hash).ok_or(Error::<T>::ClaimNotExist)?;
 
-			Proofs::<T>::insert(&claim, (sender.clone(), frame_system::Module::<T>::block_number()));
+			ensure!(claim.owner == sender, Error::<T>::NotClaimOwner);
 
-			Self::deposit_event(RawEvent::ClaimCreated(sender, claim));
+			Claims::<T>::remove(&hash);
+			ClaimHistory::<T>::remove(&hash);
+			Self::release_block_hash(claim.block_number);
+			T::Currency::unreserve(&sender, claim.deposit);
+
+			Self::deposit_event(RawEvent::ClaimRevoked(sender, hash));
 
 			Ok(())
 		}
 
-		#[weight = 0]
-		pub fn revoke_claim(origin, claim: Vec<u8>) -> dispatch::DispatchResult{
+		/// Hand one of your claims, and its deposit, over to `dest`, which must exist.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(4, 4)]
+		pub fn transfer_claim(origin, hash: T::Hash, dest: T::AccountId) -> dispatch::DispatchResult{
 			let sender = ensure_signed(origin)?;
 
-			ensure!(claim.len() <= T::MaxProofLength::get().into(), Error::<T>::ProofTooLong);
-			ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::ClaimNotExist);
+			let mut claim = Claims::<T>::get(&hash).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(claim.owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(dest != sender, Error::<T>::TransferToSelf);
+
+			T::Currency::repatriate_reserved(&sender, &dest, claim.deposit, BalanceStatus::Reserved)?;
+			Self::record_owner(&hash, dest.clone(), frame_system::Module::<T>::block_number());
+			claim.owner = dest.clone();
+			Claims::<T>::insert(&hash, claim);
+
+			Self::deposit_event(RawEvent::ClaimTransferred(sender, dest, hash));
+
+			Ok(())
+		}
 
-			let (owner, _block_number) = Proofs::<T>::get(&claim);
+		/// Anchor the `root` of a Merkle tree over the hashes of `leaf_count` documents, so that
+		/// each of them can be proven with `verify_inclusion`. `ClaimDepositBase` is reserved
+		/// until the anchor is revoked.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(4, 4)]
+		pub fn anchor_batch(origin, root: T::Hash, leaf_count: u32) -> dispatch::DispatchResult{
+			let sender = ensure_signed(origin)?;
 
-			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(leaf_count > 0, Error::<T>::EmptyBatch);
+			ensure!(!Anchors::<T>::contains_key(&root), Error::<T>::AnchorAlreadyExist);
 
-			Proofs::<T>::remove(&claim);
+			let deposit = T::ClaimDepositBase::get();
+			T::Currency::reserve(&sender, deposit)?;
 
-			Self::deposit_event(RawEvent::ClaimRevoked(sender, claim));
+			let now = frame_system::Module::<T>::block_number();
+			Self::retain_block_hash(now);
+			Anchors::<T>::insert(&root, Anchor {
+				owner: sender.clone(),
+				block_number: now,
+				timestamp: pallet_timestamp::Module::<T>::get(),
+				leaf_count,
+				deposit,
+			});
+
+			Self::deposit_event(RawEvent::BatchAnchored(sender, root, leaf_count));
 
 			Ok(())
 		}
 
-		#[weight = 0]
-		pub fn transfer_claim(origin, claim: Vec<u8>, dest: T::AccountId) -> dispatch::DispatchResult{
+		/// Revoke one of your anchors and get its deposit back.
+		#[weight = 10_000 + T::DbWeight::get().reads_writes(3, 4)]
+		pub fn revoke_anchor(origin, root: T::Hash) -> dispatch::DispatchResult{
 			let sender = ensure_signed(origin)?;
 
-			ensure!(claim.len() <= T::MaxProofLength::get().into(), Error::<T>::ProofTooLong);
-			ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::ClaimNotExist);
+			let anchor = Anchors::<T>::get(&root).ok_or(Error::<T>::AnchorNotExist)?;
 
-			let (owner, _block_number) = Proofs::<T>::get(&claim);
+			ensure!(anchor.owner == sender, Error::<T>::NotClaimOwner);
 
-			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+			Anchors::<T>::remove(&root);
+			Self::release_block_hash(anchor.block_number);
+			T::Currency::unreserve(&sender, anchor.deposit);
 
-			Proofs::<T>::insert(&claim, (dest, frame_system::Module::<T>::block_number()));
+			Self::deposit_event(RawEvent::AnchorRevoked(sender, root));
 
 			Ok(())
 		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Whether `proof` shows the document hashing to `leaf` to be in the batch anchored as `root`.
+	pub fn verify_inclusion(root: T::Hash, leaf: T::Hash, proof: Vec<T::Hash>) -> bool {
+		Anchors::<T>::contains_key(&root) && merkle::root_from_proof::<T::Hashing>(&leaf, &proof) == root
+	}
+
+	/// Move every proof of `Proofs` into `Claims`, under the hash of the document bytes it was
+	/// stored under. Proofs were not backed by deposits, so their claims get a zero `deposit`,
+	/// and they had no timestamp or metadata. A proof whose document is claimed already is
+	/// dropped. Returns the weight of the storage reads and writes made.
+	fn migrate_to_v2() -> Weight {
+		let (mut reads, mut writes): (Weight, Weight) = (0, 0);
+		let proofs = StorageIterator::<(T::AccountId, T::BlockNumber)>::new(b"PoeModule", b"Proofs").drain();
+		for (key, (owner, block_number)) in proofs {
+			reads += 2;
+			writes += 1;
+			// Keys were hashed with `blake2_128_concat`, so the encoded bytes follow the hash.
+			let bytes = match key.get(16..).and_then(|mut key| Vec::<u8>::decode(&mut key).ok()) {
+				Some(bytes) => bytes,
+				None => continue,
+			};
+			let hash = T::Hashing::hash(&bytes);
+			if Claims::<T>::contains_key(&hash) {
+				continue;
+			}
+			ClaimHistory::<T>::insert(&hash, vec![(owner.clone(), block_number)]);
+			Claims::<T>::insert(&hash, Claim {
+				owner,
+				block_number,
+				timestamp: Zero::zero(),
+				memo: None,
+				mime_type: None,
+				file_size: None,
+				deposit: Zero::zero(),
+			});
+			writes += 2;
+		}
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+
+	/// Keep the hash of block `now`, in which a claim or anchor is created, once it is known.
+	fn retain_block_hash(now: T::BlockNumber) {
+		ClaimBlockRefs::<T>::mutate(now, |refs| *refs = refs.saturating_add(1));
+		PendingBlockHash::<T>::put(now);
+	}
 
+	/// Drop the reference of a revoked claim or anchor to the hash of `block`, forgetting the
+	/// hash with the last one. Claims migrated from `Proofs` hold no reference.
+	fn release_block_hash(block: T::BlockNumber) {
+		let refs = ClaimBlockRefs::<T>::get(block).saturating_sub(1);
+		if refs == 0 {
+			ClaimBlockRefs::<T>::remove(block);
+			ClaimBlockHashes::<T>::remove(block);
+		} else {
+			ClaimBlockRefs::<T>::insert(block, refs);
+		}
+	}
 
+	/// Append `owner` to the history of the claim on `hash`. The first entry, the creator, is
+	/// kept; the oldest entries after it are dropped beyond `MaxHistoryLength`.
+	fn record_owner(hash: &T::Hash, owner: T::AccountId, now: T::BlockNumber) {
+		ClaimHistory::<T>::mutate(hash, |history| {
+			history.push((owner, now));
+			let max = T::MaxHistoryLength::get() as usize + 1;
+			if history.len() > max {
+				history.drain(1..history.len() - max + 1);
+			}
+		});
 	}
 }
@@ -0,0 +1,80 @@
+//! Merkle trees over document hashes, whose roots are anchored with `anchor_batch`.
+//!
+//! Leaves and inner nodes are hashed with different prefixes, so that a node cannot be passed
+//! off as a leaf. The two children of a node are hashed in byte order, which makes a proof just
+//! the siblings met on the way from the leaf up to the root. A node left without a sibling is
+//! carried up to the next level as it is.
+
+use sp_runtime::traits::Hash;
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+/// Hash of the leaf for document hash `leaf`.
+pub fn hash_leaf<H: Hash>(leaf: &H::Output) -> H::Output {
+	H::hash_of(&(LEAF_PREFIX, leaf))
+}
+
+/// Hash of the node whose children are `a` and `b`, in either order.
+pub fn hash_node<H: Hash>(a: &H::Output, b: &H::Output) -> H::Output {
+	if a.as_ref() <= b.as_ref() {
+		H::hash_of(&(NODE_PREFIX, a, b))
+	} else {
+		H::hash_of(&(NODE_PREFIX, b, a))
+	}
+}
+
+/// Root of the tree that `proof` places `leaf` in.
+pub fn root_from_proof<H: Hash>(leaf: &H::Output, proof: &[H::Output]) -> H::Output {
+	proof.iter().fold(hash_leaf::<H>(leaf), |node, sibling| hash_node::<H>(&node, sibling))
+}
+
+/// A tree built off chain over a batch of document hashes.
+#[cfg(feature = "std")]
+pub struct MerkleTree<H: Hash> {
+	/// Hashes of every level, leaves first and root last.
+	levels: Vec<Vec<H::Output>>,
+}
+
+#[cfg(feature = "std")]
+impl<H: Hash> MerkleTree<H> {
+	/// Build the tree over `leaves`, in order.
+	pub fn new(leaves: &[H::Output]) -> Self {
+		let mut levels = vec![leaves.iter().map(hash_leaf::<H>).collect::<Vec<_>>()];
+		while levels[levels.len() - 1].len() > 1 {
+			let next = levels[levels.len() - 1].chunks(2).map(|pair| match pair {
+				[a, b] => hash_node::<H>(a, b),
+				[a] => *a,
+				_ => unreachable!("chunks are never empty"),
+			}).collect();
+			levels.push(next);
+		}
+		MerkleTree { levels }
+	}
+
+	/// Number of leaves, to be anchored along with the root.
+	pub fn leaf_count(&self) -> u32 {
+		self.levels[0].len() as u32
+	}
+
+	/// Root of the tree. The default hash for an empty tree.
+	pub fn root(&self) -> H::Output {
+		self.levels[self.levels.len() - 1].first().copied().unwrap_or_default()
+	}
+
+	/// Proof that the leaf at `index` is in the tree, if there is one.
+	pub fn proof(&self, index: usize) -> Option<Vec<H::Output>> {
+		if index >= self.levels[0].len() {
+			return None;
+		}
+		let mut proof = Vec::new();
+		let mut index = index;
+		for level in &self.levels[..self.levels.len() - 1] {
+			if let Some(sibling) = level.get(index ^ 1) {
+				proof.push(*sibling);
+			}
+			index /= 2;
+		}
+		Some(proof)
+	}
+}
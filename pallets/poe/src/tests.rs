@@ -1,25 +1,62 @@
 use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
+use frame_support::{assert_ok, assert_noop, traits::OnInitialize};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Hash};
 use super::*;
 
+fn claim_hash() -> H256 {
+	BlakeTwo256::hash(b"document")
+}
+
+fn create(who: u64, hash: H256) -> dispatch::DispatchResult {
+	PoeModule::create_claim(Origin::signed(who), hash, None, None, None)
+}
+
 #[test]
 fn create_claim_works(){
 	new_test_ext().execute_with(|| {
-		let claim: Vec<u8> = vec![0, 1];
-		assert_ok!(PoeModule::create_claim(Origin::signed(1), claim.clone()));
+		let hash = claim_hash();
+		Timestamp::set_timestamp(1_600_000_000_000);
+		assert_ok!(PoeModule::create_claim(
+			Origin::signed(1),
+			hash,
+			Some(b"contract".to_vec()),
+			Some(b"app/pdf".to_vec()),
+			Some(1024),
+		));
+
+		assert_eq!(Claims::<Test>::get(&hash), Some(Claim {
+			owner: 1,
+			block_number: frame_system::Module::<Test>::block_number(),
+			timestamp: 1_600_000_000_000,
+			memo: Some(b"contract".to_vec()),
+			mime_type: Some(b"app/pdf".to_vec()),
+			file_size: Some(1024),
+			deposit: 25,
+		}));
+		assert_eq!(Balances::reserved_balance(1), 25);
+	})
+}
+
+#[test]
+fn claim_can_be_verified_by_hashing_the_document(){
+	new_test_ext().execute_with(|| {
+		assert_ok!(create(1, claim_hash()));
 
-		assert_eq!(Proofs::<Test>::get(&claim), (1, frame_system::Module::<Test>::block_number()));
+		let document = b"document".to_vec();
+		assert_eq!(PoeModule::claims(BlakeTwo256::hash(&document)).map(|c| c.owner), Some(1));
+		assert_eq!(PoeModule::claims(BlakeTwo256::hash(b"forged")), None);
 	})
 }
 
 #[test]
 fn create_claim_failed_when_claim_already_exit(){
 	new_test_ext().execute_with(|| {
-		let claim: Vec<u8> = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let hash = claim_hash();
+		let _ = create(1, hash);
 
 		assert_noop!(
-			PoeModule::create_claim(Origin::signed(1), claim.clone()),
+			create(1, hash),
 			Error::<Test>::ProofAlreadyExist
 		);
 	})
@@ -28,21 +65,21 @@ fn create_claim_failed_when_claim_already_exit(){
 #[test]
 fn revoke_clain_works(){
 	new_test_ext().execute_with(|| {
-		let claim: Vec<u8> = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let hash = claim_hash();
+		let _ = create(1, hash);
 
 		assert_ok!(
-			PoeModule::revoke_claim(Origin::signed(1), claim.clone())
+			PoeModule::revoke_claim(Origin::signed(1), hash)
 		);
+		assert_eq!(Claims::<Test>::get(&hash), None);
 	})
 }
 
 #[test]
 fn revoke_claim_failed_when_claim_is_not_exist() {
 	new_test_ext().execute_with(||{
-		let claim: Vec<u8> = vec![0, 1];
 		assert_noop!(
-			PoeModule::revoke_claim(Origin::signed(1), claim.clone()),
+			PoeModule::revoke_claim(Origin::signed(1), claim_hash()),
 			Error::<Test>::ClaimNotExist
 		);
 	})
@@ -51,12 +88,12 @@ fn revoke_claim_failed_when_claim_is_not_exist() {
 #[test]
 fn revoke_claim_failed_when_claim_is_not_owned() {
 	new_test_ext().execute_with(||{
-		let claim: Vec<u8> = vec![0, 1];
+		let hash = claim_hash();
 
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let _ = create(1, hash);
 
 		assert_noop!(
-			PoeModule::revoke_claim(Origin::signed(2), claim.clone()),
+			PoeModule::revoke_claim(Origin::signed(2), hash),
 			Error::<Test>::NotClaimOwner
 		);
 	})
@@ -65,21 +102,62 @@ fn revoke_claim_failed_when_claim_is_not_owned() {
 #[test]
 fn transfer_works(){
 	new_test_ext().execute_with(|| {
-		let claim: Vec<u8> = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let hash = claim_hash();
+		let _ = create(1, hash);
+
+		System::set_block_number(5);
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), hash, 2));
 
-		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+		let claim = Claims::<Test>::get(&hash).unwrap();
+		assert_eq!(claim.owner, 2);
+		assert_eq!(claim.block_number, 1);
+		assert_eq!(PoeModule::claim_history(hash), vec![(1, 1), (2, 5)]);
+		assert_eq!(
+			System::events().last().map(|e| e.event.clone()),
+			Some(TestEvent::poe_event(RawEvent::ClaimTransferred(1, 2, hash)))
+		);
+	})
+}
 
-		assert_eq!(Proofs::<Test>::get(&claim), (2, frame_system::Module::<Test>::block_number()));
+#[test]
+fn claim_history_keeps_creator_and_latest_owners(){
+	new_test_ext().execute_with(|| {
+		let hash = claim_hash();
+		let _ = create(1, hash);
+		for (block, (from, to)) in [(1, 2), (2, 1), (1, 2)].iter().enumerate() {
+			System::set_block_number(block as u64 + 2);
+			assert_ok!(PoeModule::transfer_claim(Origin::signed(*from), hash, *to));
+		}
+		assert_eq!(PoeModule::claim_history(hash), vec![(1, 1), (2, 2), (1, 3), (2, 4)]);
+
+		System::set_block_number(5);
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(2), hash, 1));
+		assert_eq!(PoeModule::claim_history(hash), vec![(1, 1), (1, 3), (2, 4), (1, 5)]);
+		assert_eq!(Claims::<Test>::get(&hash).map(|c| c.block_number), Some(1));
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), hash));
+		assert_eq!(PoeModule::claim_history(hash), vec![]);
+	})
+}
+
+#[test]
+fn transfer_fails_when_to_self(){
+	new_test_ext().execute_with(|| {
+		let hash = claim_hash();
+		let _ = create(1, hash);
+
+		assert_noop!(
+			PoeModule::transfer_claim(Origin::signed(1), hash, 1),
+			Error::<Test>::TransferToSelf
+		);
 	})
 }
 
 #[test]
 fn transfer_fails_when_claim_not_exist(){
 	new_test_ext().execute_with(|| {
-		let claim: Vec<u8> = vec![0, 1];
 		assert_noop!(
-			PoeModule::transfer_claim(Origin::signed(1), claim.clone(), 2),
+			PoeModule::transfer_claim(Origin::signed(1), claim_hash(), 2),
 			Error::<Test>::ClaimNotExist
 		);
 	})
@@ -88,11 +166,11 @@ fn transfer_fails_when_claim_not_exist(){
 #[test]
 fn transfer_tails_when_claim_not_owned(){
 	new_test_ext().execute_with(|| {
-		let claim: Vec<u8> = vec![0, 1];
-		let _ = PoeModule::create_claim(Origin::signed(1), claim.clone());
+		let hash = claim_hash();
+		let _ = create(1, hash);
 
 		assert_noop!(
-			PoeModule::transfer_claim(Origin::signed(2), claim.clone(), 2),
+			PoeModule::transfer_claim(Origin::signed(2), hash, 2),
 			Error::<Test>::NotClaimOwner
 		);
 	})
@@ -100,17 +178,212 @@ fn transfer_tails_when_claim_not_owned(){
 
 
 #[test]
-fn check_long_proof_works(){
+fn check_long_metadata_works(){
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), claim_hash(), Some(vec![0; 17]), None, None),
+			Error::<Test>::MemoTooLong
+		);
+		assert_noop!(
+			PoeModule::create_claim(Origin::signed(1), claim_hash(), None, Some(vec![0; 9]), None),
+			Error::<Test>::MimeTypeTooLong
+		);
+		assert_ok!(
+			PoeModule::create_claim(Origin::signed(1), claim_hash(), Some(vec![0; 16]), Some(vec![0; 8]), None)
+		);
+	})
+}
+
+#[test]
+fn create_claim_fails_when_deposit_not_covered(){
 	new_test_ext().execute_with(|| {
-		let mut claim:Vec<u8> = vec![];
-		let mut i = 0;
-		while i < 260 {
-			i += 1;
-			claim.push(0);
-		}
 		assert_noop!(
-			PoeModule::create_claim(Origin::signed(1), claim.clone()),
-			Error::<Test>::ProofTooLong
+			create(3, claim_hash()),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
 		);
 	})
 }
+
+#[test]
+fn deposit_moves_with_claim_and_is_refunded_on_revoke(){
+	new_test_ext().execute_with(|| {
+		let hash = claim_hash();
+		assert_ok!(PoeModule::create_claim(Origin::signed(1), hash, Some(vec![0; 5]), None, None));
+		assert_eq!(Balances::reserved_balance(1), 15);
+
+		assert_ok!(PoeModule::transfer_claim(Origin::signed(1), hash, 2));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 985);
+		assert_eq!(Balances::reserved_balance(2), 15);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(2), hash));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 1_015);
+	})
+}
+
+#[test]
+fn transfer_fails_when_dest_does_not_exist(){
+	new_test_ext().execute_with(|| {
+		let hash = claim_hash();
+		let _ = create(1, hash);
+
+		assert!(PoeModule::transfer_claim(Origin::signed(1), hash, 4).is_err());
+		assert_eq!(Claims::<Test>::get(&hash).map(|c| c.owner), Some(1));
+		assert_eq!(Balances::reserved_balance(1), 10);
+	})
+}
+
+#[test]
+fn claim_block_hash_is_kept_after_the_block(){
+	new_test_ext().execute_with(|| {
+		let hash = claim_hash();
+		let _ = create(1, hash);
+		assert_eq!(PoeModule::claim_block_hashes(1), None);
+
+		// frame_system records the hash of block 1 when block 2 is initialised.
+		System::initialize(&2, &H256::repeat_byte(1), &Default::default(), &Default::default(), Default::default());
+		PoeModule::on_initialize(2);
+		assert_eq!(PoeModule::claim_block_hashes(1), Some(H256::repeat_byte(1)));
+
+		System::initialize(&3, &H256::repeat_byte(2), &Default::default(), &Default::default(), Default::default());
+		PoeModule::on_initialize(3);
+		assert_eq!(PoeModule::claim_block_hashes(2), None);
+	})
+}
+
+#[test]
+fn claim_block_hash_is_removed_with_the_last_claim_of_the_block(){
+	new_test_ext().execute_with(|| {
+		let hash = claim_hash();
+		let root = BlakeTwo256::hash(b"root");
+		assert_ok!(create(1, hash));
+		assert_ok!(PoeModule::anchor_batch(Origin::signed(2), root, 2));
+
+		System::initialize(&2, &H256::repeat_byte(1), &Default::default(), &Default::default(), Default::default());
+		PoeModule::on_initialize(2);
+
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), hash));
+		assert_eq!(PoeModule::claim_block_hashes(1), Some(H256::repeat_byte(1)));
+		assert_ok!(PoeModule::revoke_anchor(Origin::signed(2), root));
+		assert_eq!(PoeModule::claim_block_hashes(1), None);
+
+		// A claim revoked in the block it was created in leaves nothing behind.
+		assert_ok!(create(1, hash));
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(1), hash));
+		System::initialize(&3, &H256::repeat_byte(2), &Default::default(), &Default::default(), Default::default());
+		PoeModule::on_initialize(3);
+		assert_eq!(PoeModule::claim_block_hashes(2), None);
+	})
+}
+
+fn batch(n: u32) -> Vec<H256> {
+	(0..n).map(|i| BlakeTwo256::hash_of(&i)).collect()
+}
+
+#[test]
+fn anchor_batch_works(){
+	new_test_ext().execute_with(|| {
+		let tree = merkle::MerkleTree::<BlakeTwo256>::new(&batch(5));
+		assert_ok!(PoeModule::anchor_batch(Origin::signed(1), tree.root(), tree.leaf_count()));
+
+		assert_eq!(PoeModule::anchors(tree.root()).map(|a| (a.owner, a.leaf_count)), Some((1, 5)));
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert_noop!(
+			PoeModule::anchor_batch(Origin::signed(2), tree.root(), 5),
+			Error::<Test>::AnchorAlreadyExist
+		);
+		assert_noop!(
+			PoeModule::anchor_batch(Origin::signed(1), H256::repeat_byte(1), 0),
+			Error::<Test>::EmptyBatch
+		);
+		assert_noop!(
+			PoeModule::anchor_batch(Origin::signed(3), H256::repeat_byte(1), 1),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+
+		assert_noop!(PoeModule::revoke_anchor(Origin::signed(2), tree.root()), Error::<Test>::NotClaimOwner);
+		assert_ok!(PoeModule::revoke_anchor(Origin::signed(1), tree.root()));
+		assert_eq!(PoeModule::anchors(tree.root()), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_noop!(PoeModule::revoke_anchor(Origin::signed(1), tree.root()), Error::<Test>::AnchorNotExist);
+	})
+}
+
+#[test]
+fn every_leaf_of_an_anchored_batch_can_be_proven(){
+	new_test_ext().execute_with(|| {
+		for n in 1..=9 {
+			let leaves = batch(n);
+			let tree = merkle::MerkleTree::<BlakeTwo256>::new(&leaves);
+			assert!(!PoeModule::verify_inclusion(tree.root(), leaves[0], tree.proof(0).unwrap()));
+
+			assert_ok!(PoeModule::anchor_batch(Origin::signed(1), tree.root(), n));
+			for (i, leaf) in leaves.iter().enumerate() {
+				let proof = tree.proof(i).unwrap();
+				assert!(PoeModule::verify_inclusion(tree.root(), *leaf, proof.clone()));
+				assert!(!PoeModule::verify_inclusion(tree.root(), BlakeTwo256::hash(b"forged"), proof));
+			}
+			assert_eq!(tree.proof(n as usize), None);
+		}
+	})
+}
+
+#[test]
+fn inner_node_cannot_be_proven_as_a_leaf(){
+	new_test_ext().execute_with(|| {
+		let leaves = batch(4);
+		let tree = merkle::MerkleTree::<BlakeTwo256>::new(&leaves);
+		assert_ok!(PoeModule::anchor_batch(Origin::signed(1), tree.root(), 4));
+
+		let node = merkle::hash_node::<BlakeTwo256>(
+			&merkle::hash_leaf::<BlakeTwo256>(&leaves[0]),
+			&merkle::hash_leaf::<BlakeTwo256>(&leaves[1]),
+		);
+		let proof = tree.proof(0).unwrap()[1..].to_vec();
+		assert!(!PoeModule::verify_inclusion(tree.root(), node, proof));
+	})
+}
+
+#[test]
+fn migration_keeps_old_proofs(){
+	use frame_support::{
+		Blake2_128Concat, StorageHasher,
+		storage::migration::{get_storage_value, put_storage_value},
+		traits::OnRuntimeUpgrade,
+	};
+
+	new_test_ext().execute_with(|| {
+		let old_key = |bytes: &[u8]| Blake2_128Concat::hash(&bytes.to_vec().encode());
+		put_storage_value(b"PoeModule", b"Proofs", &old_key(b"document"), (2u64, 1u64));
+		put_storage_value(b"PoeModule", b"Proofs", &old_key(b"contract"), (3u64, 4u64));
+		assert_ok!(create(1, claim_hash()));
+
+		PoeModule::on_runtime_upgrade();
+
+		assert_eq!(get_storage_value::<(u64, u64)>(b"PoeModule", b"Proofs", &old_key(b"document")), None);
+		assert_eq!(get_storage_value::<(u64, u64)>(b"PoeModule", b"Proofs", &old_key(b"contract")), None);
+		assert_eq!(StorageVersion::get(), Releases::V2_0_0);
+
+		// The old owner is kept, with no deposit to give back.
+		let hash = BlakeTwo256::hash(b"contract");
+		assert_eq!(PoeModule::claims(hash), Some(Claim {
+			owner: 3,
+			block_number: 4,
+			timestamp: 0,
+			memo: None,
+			mime_type: None,
+			file_size: None,
+			deposit: 0,
+		}));
+		assert_eq!(PoeModule::claim_history(hash), vec![(3, 4)]);
+		assert_ok!(PoeModule::revoke_claim(Origin::signed(3), hash));
+
+		// A document claimed since is left to its new owner.
+		assert_eq!(PoeModule::claims(claim_hash()).map(|claim| claim.owner), Some(1));
+		assert_eq!(PoeModule::claim_history(claim_hash()), vec![(1, 1)]);
+
+		// The migration only runs once.
+		assert_eq!(PoeModule::on_runtime_upgrade(), 0);
+	})
+}
@@ -67,6 +67,9 @@ pub type Hash = sp_core::H256;
 /// Digest item type.
 pub type DigestItem = generic::DigestItem<Hash>;
 
+/// A timestamp: milliseconds since the unix epoch.
+pub type Moment = u64;
+
 pub type KittyIndex = u32;
 
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
@@ -97,10 +100,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node-template"),
 	impl_name: create_runtime_str!("node-template"),
 	authoring_version: 1,
-	spec_version: 1,
+	spec_version: 2,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 pub const MILLISECS_PER_BLOCK: u64 = 6000;
@@ -223,8 +226,7 @@ parameter_types! {
 }
 
 impl pallet_timestamp::Trait for Runtime {
-	/// A timestamp: milliseconds since the unix epoch.
-	type Moment = u64;
+	type Moment = Moment;
 	type OnTimestampSet = Aura;
 	type MinimumPeriod = MinimumPeriod;
 	type WeightInfo = ();
@@ -270,18 +272,94 @@ impl pallet_template::Trait for Runtime {
 }
 
 parameter_types! {
-	pub const MaxProofLength:u8 = 255;
+	pub const MaxMemoLength: u32 = 256;
+	pub const MaxMimeTypeLength: u32 = 64;
+	pub const ClaimDepositBase: Balance = 1_000;
+	pub const ClaimDepositPerByte: Balance = 10;
+	pub const MaxHistoryLength: u32 = 32;
 }
 
 impl poe::Trait for Runtime {
 	type Event = Event;
-	type MaxProofLength = MaxProofLength;
+	type MaxMemoLength = MaxMemoLength;
+	type MaxMimeTypeLength = MaxMimeTypeLength;
+	type Currency = Balances;
+	type ClaimDepositBase = ClaimDepositBase;
+	type ClaimDepositPerByte = ClaimDepositPerByte;
+	type MaxHistoryLength = MaxHistoryLength;
+}
+
+parameter_types! {
+	pub const CommitDeposit: Balance = 1_000;
+	pub const RoundLength: BlockNumber = 5 * MINUTES;
+}
+
+impl pallet_commit_reveal::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type CommitDeposit = CommitDeposit;
+	type RoundLength = RoundLength;
+}
+
+parameter_types! {
+	pub const KittyReserve: Balance = 10_000;
+	pub const BreedCooldown: BlockNumber = 10 * MINUTES;
+	pub const MaxInbreedingDepth: u32 = 3;
+	pub const MaxAuctionDuration: BlockNumber = 7 * DAYS;
+	pub const MaxAuctionsEndingPerBlock: u32 = 50;
+	pub const MaxKittiesPerAccount: u32 = 1_000;
+	pub const MaxBatchSize: u32 = 100;
+	pub const MaxEggsPerAccount: u32 = 100;
+	pub const MaxHatchPerBlock: u32 = 256;
+	pub const MaxNameLength: u32 = 32;
+	pub const MaxUriLength: u32 = 256;
+	pub const MetadataDepositPerByte: Balance = 100;
 }
 
+/// Signs breeding offers with an sr25519 key generated in the benchmark's keystore.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct OfferSigner;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_kitties::OfferSigner<AccountId, Signature> for OfferSigner {
+	fn signer() -> AccountId {
+		let public = sp_io::crypto::sr25519_generate(KITTIES_KEY_TYPE, None);
+		sp_runtime::MultiSigner::from(public).into_account()
+	}
+
+	fn sign(signer: &AccountId, payload: &[u8]) -> Signature {
+		let public = sp_core::sr25519::Public::from_raw(signer.clone().into());
+		sp_io::crypto::sr25519_sign(KITTIES_KEY_TYPE, &public, payload)
+			.expect("the key of `signer()` is in the keystore; qed")
+			.into()
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+const KITTIES_KEY_TYPE: KeyTypeId = KeyTypeId(*b"kitt");
+
 impl pallet_kitties::Trait for Runtime {
 	type Event = Event;
-	type Randomness = RandomnessCollectiveFlip;
+	type Randomness = CommitReveal;
 	type KittyIndex = KittyIndex;
+	type Currency = Balances;
+	type KittyReserve = KittyReserve;
+	type BreedCooldown = BreedCooldown;
+	type MaxInbreedingDepth = MaxInbreedingDepth;
+	type MaxAuctionDuration = MaxAuctionDuration;
+	type MaxAuctionsEndingPerBlock = MaxAuctionsEndingPerBlock;
+	type MaxKittiesPerAccount = MaxKittiesPerAccount;
+	type MaxNameLength = MaxNameLength;
+	type MaxUriLength = MaxUriLength;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type MaxBatchSize = MaxBatchSize;
+	type MaxEggsPerAccount = MaxEggsPerAccount;
+	type MaxHatchPerBlock = MaxHatchPerBlock;
+	type OffchainSignature = Signature;
+	type OffchainPublic = <Signature as Verify>::Signer;
+	#[cfg(feature = "runtime-benchmarks")]
+	type OfferSigner = OfferSigner;
+	type WeightInfo = ();
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -302,7 +380,8 @@ construct_runtime!(
 		// Include the custom logic from the template pallet in the runtime.
 		TemplateModule: pallet_template::{Module, Call, Storage, Event<T>},
 		PoeModule: poe::{Module, Call, Storage, Event<T>},
-		KittiesModule: pallet_kitties::{Module, Call, Storage, Event<T>},
+		CommitReveal: pallet_commit_reveal::{Module, Call, Storage, Event<T>},
+		KittiesModule: pallet_kitties::{Module, Call, Storage, Event<T>, Config<T>},
 
 	}
 );
@@ -466,6 +545,59 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_rpc_runtime_api::KittiesApi<Block, AccountId, KittyIndex> for Runtime {
+		fn kitties_of(account: AccountId, start: u32, limit: u32) -> Vec<KittyIndex> {
+			KittiesModule::kitties_of(&account, start, limit)
+		}
+
+		fn owned_kitties_count(account: AccountId) -> u32 {
+			KittiesModule::owned_kitties_count(account)
+		}
+
+		fn kitty_owner(kitty_id: KittyIndex) -> Option<AccountId> {
+			KittiesModule::kitty_owner(kitty_id)
+		}
+
+		fn kitty_dna(kitty_id: KittyIndex) -> Option<[u8; 16]> {
+			KittiesModule::kitty_dna(kitty_id)
+		}
+
+		fn parents(kitty_id: KittyIndex) -> Vec<KittyIndex> {
+			KittiesModule::parents_of(kitty_id)
+		}
+
+		fn children(kitty_id: KittyIndex) -> Vec<KittyIndex> {
+			KittiesModule::children_of(kitty_id)
+		}
+
+		fn breeding_partners(kitty_id: KittyIndex) -> Vec<KittyIndex> {
+			KittiesModule::breeding_partners_of(kitty_id)
+		}
+
+		fn kitties_count() -> KittyIndex {
+			KittiesModule::kitties_count()
+		}
+	}
+
+	impl poe_rpc_runtime_api::PoeApi<Block, AccountId, BlockNumber, Hash, Moment> for Runtime {
+		fn claim_info(hash: Hash) -> Option<poe_rpc_runtime_api::ClaimInfo<AccountId, BlockNumber, Hash, Moment>> {
+			PoeModule::claims(hash).map(|claim| poe_rpc_runtime_api::ClaimInfo {
+				owner: claim.owner,
+				block_number: claim.block_number,
+				block_hash: PoeModule::claim_block_hashes(claim.block_number),
+				timestamp: claim.timestamp,
+			})
+		}
+
+		fn claim_history(hash: Hash) -> Vec<(AccountId, BlockNumber)> {
+			PoeModule::claim_history(hash)
+		}
+
+		fn verify_inclusion(root: Hash, leaf: Hash, proof: Vec<Hash>) -> bool {
+			PoeModule::verify_inclusion(root, leaf, proof)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -500,6 +632,7 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+			add_benchmark!(params, batches, pallet_kitties, KittiesModule);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)